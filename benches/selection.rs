@@ -0,0 +1,49 @@
+// Ranking cost per generation on a 50k-member population: a full sort
+// against the partial selection plus prefix sort generate_next_generation
+// uses. Run with `cargo bench --bench selection`.
+
+use std::time::{Duration, Instant};
+
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+use genetic::chromosome::rank;
+use genetic::{initial_population, instances, Path};
+
+const POPULATION: usize = 50_000;
+const ROUNDS: u32 = 20;
+
+fn time<F: FnMut(&mut Vec<Path>)>(population: &[Path], mut rank_members: F) -> Duration {
+    let mut total = Duration::default();
+    for _ in 0..ROUNDS {
+        let mut members = population.to_vec();
+        let started = Instant::now();
+        rank_members(&mut members);
+        total += started.elapsed();
+    }
+    total / ROUNDS
+}
+
+fn main() {
+    let mut rng = SmallRng::seed_from_u64(1);
+    let cities = instances::clustered(8, 25, 5.0, 100.0, &mut rng).expect("valid instance");
+    let population = initial_population(&cities, POPULATION, &mut rng);
+
+    let full = time(&population, |members| members.sort_by(rank));
+    println!("full sort of {}: {:?}", POPULATION, full);
+
+    for &share in &[0.8, 0.5, 0.2] {
+        let k = (POPULATION as f64 * share) as usize;
+        let partial = time(&population, |members| {
+            members.select_nth_unstable_by(k - 1, rank);
+            members[..k].sort_by(rank);
+        });
+        println!(
+            "partial selection of the top {} ({:.0}%): {:?} ({:.2}x faster)",
+            k,
+            share * 100.0,
+            partial,
+            full.as_secs_f64() / partial.as_secs_f64()
+        );
+    }
+}
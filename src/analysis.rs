@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::city::City;
+use crate::error::Error;
+
+// What `genetic-rust inspect` reports about an instance before a run.
+#[derive(Clone, Debug)]
+pub struct InstanceSummary {
+    pub cities: usize,
+    pub min: (f64, f64),
+    pub max: (f64, f64),
+    // Cities at the same coordinates as an earlier city.
+    pub duplicates: usize,
+    pub nearest_neighbour_length: f64,
+    pub mst_lower_bound: f64,
+    // Bytes a full f64 distance matrix would take.
+    pub matrix_bytes: usize,
+}
+
+impl fmt::Display for InstanceSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "cities: {}", self.cities)?;
+        writeln!(f, "bounding box: ({}, {}) - ({}, {})", self.min.0, self.min.1, self.max.0, self.max.1)?;
+        writeln!(f, "duplicate coordinates: {}", self.duplicates)?;
+        writeln!(f, "nearest-neighbour tour length: {}", self.nearest_neighbour_length)?;
+        writeln!(f, "MST lower bound: {}", self.mst_lower_bound)?;
+        write!(f, "distance matrix memory: {}", human_bytes(self.matrix_bytes))
+    }
+}
+
+pub fn summarize(cities: &[City]) -> Result<InstanceSummary, Error> {
+    if cities.is_empty() {
+        return Err(Error::EmptyCityList);
+    }
+
+    let mut min = (f64::INFINITY, f64::INFINITY);
+    let mut max = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    let mut seen = HashSet::new();
+    let mut duplicates = 0;
+    for c in cities {
+        min = (min.0.min(c.x), min.1.min(c.y));
+        max = (max.0.max(c.x), max.1.max(c.y));
+        if !seen.insert((c.x.to_bits(), c.y.to_bits())) {
+            duplicates += 1;
+        }
+    }
+
+    let tour = nearest_neighbour_tour(cities);
+    let closing = cities[tour[tour.len() - 1]].distance_to(&cities[tour[0]]);
+    let nearest_neighbour_length = tour.windows(2)
+        .map(|w| cities[w[0]].distance_to(&cities[w[1]]))
+        .sum::<f64>() + closing;
+
+    Ok(InstanceSummary {
+        cities: cities.len(),
+        min,
+        max,
+        duplicates,
+        nearest_neighbour_length,
+        mst_lower_bound: mst_weight(cities),
+        matrix_bytes: cities.len() * cities.len() * std::mem::size_of::<f64>(),
+    })
+}
+
+// Greedy tour from city 0, always moving to the closest unvisited city.
+pub fn nearest_neighbour_tour(cities: &[City]) -> Vec<usize> {
+    let mut visited = vec![false; cities.len()];
+    let mut tour = Vec::with_capacity(cities.len());
+    let mut current = 0;
+
+    while tour.len() < cities.len() {
+        visited[current] = true;
+        tour.push(current);
+
+        let next = (0..cities.len())
+            .filter(|&i| !visited[i])
+            .min_by(|&a, &b| {
+                let da = cities[current].distance_to(&cities[a]);
+                let db = cities[current].distance_to(&cities[b]);
+                da.partial_cmp(&db).unwrap_or(std::cmp::Ordering::Equal)
+            });
+        match next {
+            Some(next) => current = next,
+            None => break,
+        }
+    }
+
+    tour
+}
+
+// Weight of a minimum spanning tree over the Euclidean distances (Prim,
+// O(n^2)). Every path through all cities is a spanning tree, so no open tour
+// can be shorter.
+pub fn mst_weight(cities: &[City]) -> f64 {
+    spanning_tree(cities.len(), |a, b| cities[a].distance_to(&cities[b]), None)
+}
+
+// The 1-tree bound for closed tours: a spanning tree over every city but the
+// first, plus the first city's two shortest edges. A closed tour is itself a
+// 1-tree, so none is shorter than the minimum one.
+pub fn one_tree_bound(cities: &[City]) -> f64 {
+    one_tree(cities.len(), |a, b| cities[a].distance_to(&cities[b]))
+}
+
+// The 1-tree bound over any symmetric distance between `size` cities.
+pub(crate) fn one_tree<F: Fn(usize, usize) -> f64>(size: usize, distance: F) -> f64 {
+    if size < 3 {
+        return 2.0 * spanning_tree(size, distance, None);
+    }
+
+    let mut edges: Vec<f64> = (1..size).map(|c| distance(0, c)).collect();
+    edges.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    spanning_tree(size, distance, Some(0)) + edges[0] + edges[1]
+}
+
+pub(crate) fn spanning_tree<F: Fn(usize, usize) -> f64>(size: usize, distance: F, skip: Option<usize>) -> f64 {
+    let mut in_tree = vec![false; size];
+    if let Some(skip) = skip {
+        in_tree[skip] = true;
+    }
+    let root = match (0..size).find(|&i| !in_tree[i]) {
+        Some(root) => root,
+        None => return 0.0,
+    };
+
+    let mut nearest = vec![f64::INFINITY; size];
+    let mut total = 0.0;
+    nearest[root] = 0.0;
+
+    for _ in 0..size {
+        let mut next = None;
+        for i in 0..size {
+            if !in_tree[i] && next.is_none_or(|n: usize| nearest[i] < nearest[n]) {
+                next = Some(i);
+            }
+        }
+        let next = match next {
+            Some(next) => next,
+            None => break,
+        };
+
+        in_tree[next] = true;
+        total += nearest[next];
+        for i in 0..size {
+            if !in_tree[i] {
+                nearest[i] = nearest[i].min(distance(next, i));
+            }
+        }
+    }
+
+    total
+}
+
+fn human_bytes(bytes: usize) -> String {
+    let units = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < units.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, units[unit])
+    }
+}
@@ -0,0 +1,59 @@
+use std::cmp::Ordering;
+
+use crate::chromosome::{rank, Chromosome, Distance};
+
+// Near-optimal but structurally different solutions. A candidate is admitted
+// while its cost is within `epsilon` (relative) of the best known cost and it
+// is at least `min_distance` away from every other member; a newcomer that is
+// too similar to a member replaces it only if it is fitter.
+#[derive(Clone)]
+pub struct Archive<C> {
+    epsilon: f64,
+    min_distance: f64,
+    capacity: usize,
+    distance: fn(&C, &C) -> f64,
+    members: Vec<C>,
+}
+
+impl<C: Chromosome + Distance> Archive<C> {
+    pub fn new(epsilon: f64, min_distance: f64, capacity: usize) -> Archive<C> {
+        Archive { epsilon, min_distance, capacity, distance: C::distance, members: Vec::new() }
+    }
+}
+
+impl<C: Chromosome> Archive<C> {
+    pub fn members(&self) -> &[C] {
+        &self.members
+    }
+
+    pub fn clear(&mut self) {
+        self.members.clear();
+    }
+
+    pub fn update(&mut self, best: &C, candidates: &[C]) {
+        let max_cost = (1.0 + self.epsilon) / best.fitness();
+
+        for candidate in std::iter::once(best).chain(candidates) {
+            if 1.0 / candidate.fitness() > max_cost {
+                continue;
+            }
+
+            let distance = self.distance;
+            let similar = self.members.iter()
+                .position(|m| distance(m, candidate) < self.min_distance);
+
+            match similar {
+                Some(i) => {
+                    if rank(candidate, &self.members[i]) == Ordering::Less {
+                        self.members[i] = candidate.clone();
+                    }
+                }
+                None => self.members.push(candidate.clone()),
+            }
+        }
+
+        self.members.retain(|m| 1.0 / m.fitness() <= max_cost);
+        self.members.sort_by(rank);
+        self.members.truncate(self.capacity);
+    }
+}
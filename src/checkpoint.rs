@@ -0,0 +1,259 @@
+use std::io::Write;
+use std::str::FromStr;
+
+use crate::city::City;
+use crate::cost::{CostMode, CostModel, Objective, TourType};
+use crate::error::Error;
+use crate::json::{self, quote};
+
+const HEADER: &str = "genetic-checkpoint 1";
+
+// The state Simulation::save_checkpoint writes and Simulation::resume reads,
+// as a line-based text file. Floats are written in Rust's shortest
+// round-trip form, so a resumed run sees bit-identical values.
+pub(crate) struct Checkpoint {
+    pub(crate) generation: usize,
+    pub(crate) last_improvement: usize,
+    pub(crate) max_iterations: usize,
+    pub(crate) crossover_rate: f64,
+    pub(crate) mutation_rate: f64,
+    pub(crate) survival_rate: f64,
+    pub(crate) elitism_count: usize,
+    pub(crate) cost_model: CostModel,
+    pub(crate) seed: u64,
+    // The master seed, when every generation is bred from its own seed.
+    pub(crate) generation_seeds: Option<u64>,
+    pub(crate) cities: Vec<City>,
+    pub(crate) population: Vec<Vec<usize>>,
+    pub(crate) fittest: Vec<usize>,
+}
+
+fn join(order: &[usize]) -> String {
+    order.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+impl Checkpoint {
+    pub(crate) fn write<W: Write>(&self, mut w: W) -> Result<(), Error> {
+        writeln!(w, "{}", HEADER)?;
+        writeln!(w, "generation {}", self.generation)?;
+        writeln!(w, "last_improvement {}", self.last_improvement)?;
+        writeln!(w, "max_iterations {}", self.max_iterations)?;
+        writeln!(w, "rates {} {} {}", self.crossover_rate, self.mutation_rate, self.survival_rate)?;
+        writeln!(w, "elitism {}", self.elitism_count)?;
+        match self.cost_model.mode {
+            CostMode::Float => writeln!(w, "cost_mode float")?,
+            CostMode::Integer { scale } => writeln!(w, "cost_mode integer {}", scale)?,
+        }
+        match self.cost_model.objective {
+            Objective::Distance => writeln!(w, "objective distance")?,
+            Objective::Duration { speed } => writeln!(w, "objective duration {}", speed)?,
+        }
+        match self.cost_model.tour_type {
+            TourType::Closed => writeln!(w, "tour closed")?,
+            TourType::Open => writeln!(w, "tour open")?,
+        }
+        match self.generation_seeds {
+            Some(master) => writeln!(w, "rng {} {}", self.seed, master)?,
+            None => writeln!(w, "rng {}", self.seed)?,
+        }
+
+        writeln!(w, "cities {}", self.cities.len())?;
+        for c in &self.cities {
+            let reward = c.reward.map_or("-".to_string(), |r| r.to_string());
+            match &c.name {
+                Some(name) => writeln!(w, "{} {} {} {} {}", c.x, c.y, c.service_time, reward, quote(name))?,
+                None => writeln!(w, "{} {} {} {}", c.x, c.y, c.service_time, reward)?,
+            }
+        }
+
+        writeln!(w, "population {}", self.population.len())?;
+        for order in &self.population {
+            writeln!(w, "{}", join(order))?;
+        }
+        writeln!(w, "fittest {}", join(&self.fittest))?;
+        Ok(())
+    }
+
+    pub(crate) fn parse(text: &str) -> Result<Checkpoint, Error> {
+        let mut lines = Lines { lines: text.lines().enumerate() };
+
+        let (line, header) = lines.next_line()?;
+        if header.trim() != HEADER {
+            return Err(Error::Parse { line, reason: "not a checkpoint file".to_string() });
+        }
+
+        let generation = lines.keyed("generation")?.single()?;
+        let last_improvement = lines.keyed("last_improvement")?.single()?;
+        let max_iterations = lines.keyed("max_iterations")?.single()?;
+        let rates = lines.keyed("rates")?;
+        let (crossover_rate, mutation_rate, survival_rate) = (rates.field(0)?, rates.field(1)?, rates.field(2)?);
+        let elitism_count = lines.keyed("elitism")?.single()?;
+
+        let mode = lines.keyed("cost_mode")?;
+        let mode = match mode.word(0)? {
+            "float" => CostMode::Float,
+            "integer" => CostMode::Integer { scale: mode.field(1)? },
+            other => return Err(mode.error(format!("unknown cost mode {}", other))),
+        };
+        let objective = lines.keyed("objective")?;
+        let objective = match objective.word(0)? {
+            "distance" => Objective::Distance,
+            "duration" => Objective::Duration { speed: objective.field(1)? },
+            other => return Err(objective.error(format!("unknown objective {}", other))),
+        };
+        let tour = lines.keyed("tour")?;
+        let tour_type = match tour.word(0)? {
+            "closed" => TourType::Closed,
+            "open" => TourType::Open,
+            other => return Err(tour.error(format!("unknown tour type {}", other))),
+        };
+        let rng = lines.keyed("rng")?;
+        let seed = rng.field(0)?;
+        let generation_seeds = if rng.words.len() > 1 { Some(rng.field(1)?) } else { None };
+
+        let count: usize = lines.keyed("cities")?.single()?;
+        let mut cities = Vec::with_capacity(count);
+        for _ in 0..count {
+            let (line, text) = lines.next_line()?;
+            let fields = Fields { line, words: text.split_whitespace().collect() };
+            let reward = match fields.word(3)? {
+                "-" => None,
+                _ => Some(fields.field(3)?),
+            };
+            // Anything after the fourth field is the city's name as a JSON
+            // string, so newlines and surrounding spaces survive.
+            let name = match text.trim().splitn(5, ' ').nth(4) {
+                Some(quoted) => match json::parse(quoted) {
+                    Ok(json::Value::String(name)) => Some(name),
+                    _ => return Err(fields.error(format!("invalid city name {}", quoted))),
+                },
+                None => None,
+            };
+            cities.push(City { x: fields.field(0)?, y: fields.field(1)?, service_time: fields.field(2)?, reward, name });
+        }
+
+        let count: usize = lines.keyed("population")?.single()?;
+        let mut population = Vec::with_capacity(count);
+        for _ in 0..count {
+            let (line, text) = lines.next_line()?;
+            population.push(Fields { line, words: text.split_whitespace().collect() }.all()?);
+        }
+        let fittest = lines.keyed("fittest")?.all()?;
+
+        Ok(Checkpoint {
+            generation,
+            last_improvement,
+            max_iterations,
+            crossover_rate,
+            mutation_rate,
+            survival_rate,
+            elitism_count,
+            cost_model: CostModel { mode, objective, tour_type },
+            seed,
+            generation_seeds,
+            cities,
+            population,
+            fittest,
+        })
+    }
+}
+
+struct Lines<'a, I: Iterator<Item = (usize, &'a str)>> {
+    lines: I,
+}
+
+impl<'a, I: Iterator<Item = (usize, &'a str)>> Lines<'a, I> {
+    fn next_line(&mut self) -> Result<(usize, &'a str), Error> {
+        self.lines.next()
+            .map(|(i, text)| (i + 1, text))
+            .ok_or(Error::Parse { line: 0, reason: "unexpected end of checkpoint".to_string() })
+    }
+
+    fn keyed(&mut self, key: &str) -> Result<Fields<'a>, Error> {
+        let (line, text) = self.next_line()?;
+        let mut words = text.split_whitespace();
+        if words.next() != Some(key) {
+            return Err(Error::Parse { line, reason: format!("expected {}", key) });
+        }
+        Ok(Fields { line, words: words.collect() })
+    }
+}
+
+struct Fields<'a> {
+    line: usize,
+    words: Vec<&'a str>,
+}
+
+impl<'a> Fields<'a> {
+    fn error(&self, reason: String) -> Error {
+        Error::Parse { line: self.line, reason }
+    }
+
+    fn word(&self, i: usize) -> Result<&'a str, Error> {
+        self.words.get(i).copied().ok_or_else(|| self.error("missing value".to_string()))
+    }
+
+    fn field<T: FromStr>(&self, i: usize) -> Result<T, Error> {
+        let word = self.word(i)?;
+        word.parse().map_err(|_| self.error(format!("invalid value {}", word)))
+    }
+
+    fn single<T: FromStr>(&self) -> Result<T, Error> {
+        self.field(0)
+    }
+
+    fn all<T: FromStr>(&self) -> Result<Vec<T>, Error> {
+        (0..self.words.len()).map(|i| self.field(i)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(cities: Vec<City>) -> Checkpoint {
+        Checkpoint {
+            generation: 3,
+            last_improvement: 2,
+            max_iterations: 10,
+            crossover_rate: 0.8,
+            mutation_rate: 0.1,
+            survival_rate: 0.2,
+            elitism_count: 1,
+            cost_model: CostModel::default(),
+            seed: 42,
+            generation_seeds: None,
+            population: vec![(0..cities.len()).collect()],
+            fittest: (0..cities.len()).collect(),
+            cities,
+        }
+    }
+
+    fn round_trip(checkpoint: &Checkpoint) -> Checkpoint {
+        let mut text = Vec::new();
+        checkpoint.write(&mut text).unwrap();
+        Checkpoint::parse(&String::from_utf8(text).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn awkward_city_names_survive_a_round_trip() {
+        let names = ["Berlin", "  padded  ", "two\nlines", "tab\there", "quote \" and \\", "", "4 5 6 7"];
+        let cities: Vec<City> = names.iter().enumerate()
+            .map(|(i, name)| City::new(i as f64, 0.5).named(name))
+            .chain(Some(City::new(-1.0, 2.0)))
+            .collect();
+
+        let parsed = round_trip(&checkpoint(cities.clone()));
+        let names: Vec<Option<String>> = parsed.cities.iter().map(|c| c.name.clone()).collect();
+        assert_eq!(names, cities.iter().map(|c| c.name.clone()).collect::<Vec<_>>());
+        assert_eq!(parsed.population, vec![(0..8).collect::<Vec<usize>>()]);
+    }
+
+    #[test]
+    fn unquoted_names_are_rejected() {
+        let mut text = Vec::new();
+        checkpoint(vec![City::new(0.0, 0.0).named("x"), City::new(1.0, 0.0)]).write(&mut text).unwrap();
+        let text = String::from_utf8(text).unwrap().replace("\"x\"", "x");
+        assert!(matches!(Checkpoint::parse(&text), Err(Error::Parse { .. })));
+    }
+}
@@ -0,0 +1,66 @@
+use std::cmp::Ordering;
+
+use rand::Rng;
+
+// A genome the evolutionary loop can work with. `Problem` carries whatever
+// the genome needs to evaluate itself (the city list for a tour, a fitness
+// function for a bitstring); the simulation owns one and hands it to every
+// operator call. Operators are expected to leave the chromosome evaluated, so
+// `fitness` is a cheap read.
+pub trait Chromosome: Clone {
+    type Problem;
+
+    fn fitness(&self) -> f64;
+
+    fn crossover<R: Rng>(&self, other: &Self, problem: &Self::Problem, rng: &mut R) -> Self;
+
+    fn mutate<R: Rng>(&mut self, problem: &Self::Problem, rng: &mut R);
+
+    // Recomputes fitness after the problem itself has changed.
+    fn evaluate(&mut self, problem: &Self::Problem);
+
+    // Secondary ordering used when fitness ties, so ranking is reproducible.
+    fn tie_break(&self, _other: &Self) -> Ordering {
+        Ordering::Equal
+    }
+
+    // Platform-independent digest of the genes, folded into the per-generation
+    // population hash.
+    fn fingerprint(&self) -> u64 {
+        0
+    }
+}
+
+// Structural distance between two genomes, e.g. differing edges for a tour or
+// Hamming distance for a bitstring.
+pub trait Distance {
+    fn distance(&self, other: &Self) -> f64;
+}
+
+// Fittest first, falling back to the chromosome's own tie-break.
+pub fn rank<C: Chromosome>(a: &C, b: &C) -> Ordering {
+    b.fitness().partial_cmp(&a.fitness())
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| a.tie_break(b))
+}
+
+// 64-bit FNV-1a; unlike DefaultHasher its output is fixed across platforms
+// and toolchains.
+pub(crate) struct Fnv(u64);
+
+impl Fnv {
+    pub(crate) fn new() -> Fnv {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub(crate) fn feed(&mut self, value: u64) {
+        for byte in value.to_le_bytes().iter() {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,324 @@
+use std::cmp::Ordering;
+use std::io::BufRead;
+
+use rand::Rng;
+
+use crate::error::Error;
+use crate::json::{self, Value};
+
+#[derive(Clone)]
+pub struct City {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) service_time: f64,
+    pub(crate) reward: Option<f64>,
+    pub(crate) name: Option<String>,
+}
+
+impl City {
+    pub fn new(x: f64, y: f64) -> City {
+        City { x, y, service_time: 0.0, reward: None, name: None }
+    }
+
+    // A city that keeps the vehicle busy for `service_time` once reached.
+    pub fn with_service_time(x: f64, y: f64, service_time: f64) -> City {
+        City { x, y, service_time, reward: None, name: None }
+    }
+
+    // A city the tour may skip, forfeiting `reward`. Cities built with `new`
+    // must always be visited.
+    pub fn optional(x: f64, y: f64, reward: f64) -> City {
+        City { x, y, service_time: 0.0, reward: Some(reward), name: None }
+    }
+
+    // The same city labelled `name`, which is only carried along for output.
+    pub fn named(mut self, name: &str) -> City {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn is_mandatory(&self) -> bool {
+        self.reward.is_none()
+    }
+
+    pub fn distance_to(&self, other: &City) -> f64 {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    // Reads one city per line, with fields separated by commas (double
+    // quotes may enclose a field) or by whitespace. Blank lines and lines
+    // starting with '#' are skipped. A header row names the columns: x (or
+    // lat/latitude), y (or lon/lng/longitude), name (or label), service_time
+    // and reward, matched case-insensitively; other columns are ignored and
+    // an empty reward leaves the city mandatory. Without a header the
+    // columns are x, y and an optional name.
+    pub fn from_csv<R: BufRead>(reader: R) -> Result<Vec<City>, Error> {
+        let mut columns: Option<Columns> = None;
+        let mut cities = Vec::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |reason: String| Error::Parse { line: number + 1, reason };
+
+            let fields = split_fields(line);
+            let numeric = fields.len() >= 2 && fields[..2].iter().all(|f| f.parse::<f64>().is_ok());
+            if columns.is_none() && !numeric {
+                columns = Some(Columns::from_header(&fields).map_err(|reason| error(reason.to_string()))?);
+                continue;
+            }
+            let columns = columns.get_or_insert_with(Columns::default);
+
+            let number_at = |column: usize, what: &str| {
+                let field = fields.get(column).map_or("", |f| f.as_str());
+                field.parse::<f64>().map_err(|_| error(format!("invalid {} {:?}", what, field)))
+            };
+            let present = |column: Option<usize>| {
+                column.and_then(|c| fields.get(c)).filter(|f| !f.is_empty())
+            };
+
+            let mut city = City::new(number_at(columns.x, "x")?, number_at(columns.y, "y")?);
+            if let Some(column) = columns.service_time.filter(|&c| present(Some(c)).is_some()) {
+                city.service_time = number_at(column, "service_time")?;
+            }
+            if let Some(column) = columns.reward.filter(|&c| present(Some(c)).is_some()) {
+                city.reward = Some(number_at(column, "reward")?);
+            }
+            city.name = present(columns.name).cloned();
+            cities.push(city);
+        }
+
+        Ok(cities)
+    }
+
+    // Reads the points of a GeoJSON FeatureCollection, Feature or bare
+    // Point/MultiPoint geometry. GeoJSON orders coordinates longitude first;
+    // each city gets x = latitude and y = longitude, the convention Haversine
+    // expects. A feature's "name" (or "label") property becomes the city's
+    // name. Features with other geometry types are skipped.
+    pub fn from_geojson<R: BufRead>(mut reader: R) -> Result<Vec<City>, Error> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let root = json::parse(&text)?;
+
+        let mut cities = Vec::new();
+        match root.get("type").and_then(Value::as_str) {
+            Some("FeatureCollection") => {
+                let features = root.get("features").and_then(Value::as_array).ok_or_else(|| geojson_error("features"))?;
+                for feature in features {
+                    push_feature(feature, &mut cities)?;
+                }
+            }
+            Some("Feature") => push_feature(&root, &mut cities)?,
+            Some(_) => push_geometry(&root, None, &mut cities)?,
+            None => return Err(geojson_error("type")),
+        }
+
+        Ok(cities)
+    }
+}
+
+// Where each CSV field lives in a row.
+struct Columns {
+    x: usize,
+    y: usize,
+    name: Option<usize>,
+    service_time: Option<usize>,
+    reward: Option<usize>,
+}
+
+impl Default for Columns {
+    fn default() -> Columns {
+        Columns { x: 0, y: 1, name: Some(2), service_time: None, reward: None }
+    }
+}
+
+impl Columns {
+    fn from_header(fields: &[String]) -> Result<Columns, String> {
+        let find = |names: &[&str]| fields.iter().position(|f| names.contains(&f.to_lowercase().as_str()));
+        Ok(Columns {
+            x: find(&["x", "lat", "latitude"]).ok_or("header has no x column")?,
+            y: find(&["y", "lon", "lng", "longitude"]).ok_or("header has no y column")?,
+            name: find(&["name", "label"]),
+            service_time: find(&["service_time"]),
+            reward: find(&["reward"]),
+        })
+    }
+}
+
+fn split_fields(line: &str) -> Vec<String> {
+    if !line.contains(',') {
+        return line.split_whitespace().map(str::to_string).collect();
+    }
+
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+fn geojson_error(member: &str) -> Error {
+    Error::Parse { line: 1, reason: format!("GeoJSON object has no valid \"{}\"", member) }
+}
+
+fn push_feature(feature: &Value, cities: &mut Vec<City>) -> Result<(), Error> {
+    let properties = feature.get("properties");
+    let name = ["name", "label"].iter()
+        .find_map(|key| properties.and_then(|p| p.get(key)).and_then(Value::as_str));
+    match feature.get("geometry") {
+        Some(Value::Null) | None => Ok(()),
+        Some(geometry) => push_geometry(geometry, name, cities),
+    }
+}
+
+fn push_geometry(geometry: &Value, name: Option<&str>, cities: &mut Vec<City>) -> Result<(), Error> {
+    let coordinates = geometry.get("coordinates");
+    let points = match geometry.get("type").and_then(Value::as_str) {
+        Some("Point") => coordinates.map(std::slice::from_ref),
+        Some("MultiPoint") => coordinates.and_then(Value::as_array),
+        _ => return Ok(()),
+    };
+    for point in points.ok_or_else(|| geojson_error("coordinates"))? {
+        let position = point.as_array().unwrap_or(&[]);
+        match (position.first().and_then(Value::as_f64), position.get(1).and_then(Value::as_f64)) {
+            (Some(lon), Some(lat)) => {
+                let city = City::new(lat, lon);
+                cities.push(match name {
+                    Some(name) => city.named(name),
+                    None => city,
+                });
+            }
+            _ => return Err(geojson_error("coordinates")),
+        }
+    }
+    Ok(())
+}
+
+// Picks `count` distinct cities with probability proportional to their
+// priority (weighted sampling without replacement, Efraimidis-Spirakis), so a
+// day's tour can be planned over a subset of a much larger candidate set.
+// Returns indices into `cities` in ascending order; cities with a
+// non-positive priority are never picked.
+pub fn sample_weighted<R: Rng>(
+    cities: &[City],
+    priorities: &[f64],
+    count: usize,
+    rng: &mut R,
+) -> Result<Vec<usize>, Error> {
+    if cities.len() != priorities.len() {
+        return Err(Error::LengthMismatch { expected: cities.len(), found: priorities.len() });
+    }
+
+    let mut keyed: Vec<(f64, usize)> = priorities.iter().enumerate()
+        .filter(|(_, &w)| w > 0.0)
+        .map(|(i, &w)| (rng.gen::<f64>().powf(1.0 / w), i))
+        .collect();
+    keyed.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal).then(a.1.cmp(&b.1)));
+    keyed.truncate(count);
+
+    let mut picked: Vec<usize> = keyed.into_iter().map(|(_, i)| i).collect();
+    picked.sort_unstable();
+    Ok(picked)
+}
+
+// Indices outside `cities` are skipped.
+pub fn subset(cities: &[City], indices: &[usize]) -> Vec<City> {
+    indices.iter().filter_map(|&i| cities.get(i).cloned()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn csv(text: &str) -> Result<Vec<City>, Error> {
+        City::from_csv(text.as_bytes())
+    }
+
+    fn geojson(text: &str) -> Result<Vec<City>, Error> {
+        City::from_geojson(text.as_bytes())
+    }
+
+    #[test]
+    fn csv_without_a_header_reads_x_y_and_name() {
+        let cities = csv("# depots\n1, 2, Depot\n\n3 4\n").unwrap();
+        assert_eq!(cities.len(), 2);
+        assert_eq!((cities[0].x, cities[0].y, cities[0].name()), (1.0, 2.0, Some("Depot")));
+        assert_eq!((cities[1].x, cities[1].y, cities[1].name()), (3.0, 4.0, None));
+    }
+
+    #[test]
+    fn csv_header_names_the_columns() {
+        let text = "Label,Longitude,Latitude,Reward,Service_Time,notes\nBerlin,13.4,52.5,,5,capital\nPotsdam,13.07,52.4,2.5,,\n";
+        let cities = csv(text).unwrap();
+        assert_eq!((cities[0].x, cities[0].y), (52.5, 13.4));
+        assert_eq!((cities[0].name(), cities[0].reward, cities[0].service_time), (Some("Berlin"), None, 5.0));
+        assert_eq!((cities[1].name(), cities[1].reward, cities[1].service_time), (Some("Potsdam"), Some(2.5), 0.0));
+    }
+
+    #[test]
+    fn csv_fields_may_be_quoted() {
+        let cities = csv("x,y,name\n1,2,\"Halle, Saale\"\n3,4,\"The \"\"Old\"\" Town\"\n").unwrap();
+        assert_eq!(cities[0].name(), Some("Halle, Saale"));
+        assert_eq!(cities[1].name(), Some("The \"Old\" Town"));
+    }
+
+    #[test]
+    fn malformed_csv_is_an_error() {
+        assert!(matches!(csv("name,y\nA,1\n"), Err(Error::Parse { line: 1, .. })));
+        assert!(matches!(csv("x,y\n1,2\n3,zz\n"), Err(Error::Parse { line: 3, .. })));
+        assert!(matches!(csv("1,2\n3\n"), Err(Error::Parse { line: 2, .. })));
+        assert!(matches!(csv("x,y,reward\n1,2,lots\n"), Err(Error::Parse { line: 2, .. })));
+    }
+
+    #[test]
+    fn geojson_points_become_named_cities() {
+        let text = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"name": "Berlin"}, "geometry": {"type": "Point", "coordinates": [13.4, 52.5]}},
+            {"type": "Feature", "properties": {"label": "Stops"}, "geometry": {"type": "MultiPoint", "coordinates": [[1, 2], [3, 4, 99]]}},
+            {"type": "Feature", "properties": null, "geometry": {"type": "LineString", "coordinates": [[0, 0], [1, 1]]}},
+            {"type": "Feature", "properties": {}, "geometry": null}
+        ]}"#;
+        let cities = geojson(text).unwrap();
+        let read: Vec<(f64, f64, Option<&str>)> = cities.iter().map(|c| (c.x, c.y, c.name())).collect();
+        assert_eq!(read, vec![(52.5, 13.4, Some("Berlin")), (2.0, 1.0, Some("Stops")), (4.0, 3.0, Some("Stops"))]);
+    }
+
+    #[test]
+    fn bare_geometries_are_read() {
+        let point = geojson(r#"{"type": "Point", "coordinates": [-0.1, 51.5]}"#).unwrap();
+        assert_eq!((point[0].x, point[0].y, point[0].name()), (51.5, -0.1, None));
+        let feature = geojson(r#"{"type": "Feature", "properties": {"name": "A"}, "geometry": {"type": "Point", "coordinates": [1, 2]}}"#).unwrap();
+        assert_eq!(feature[0].name(), Some("A"));
+        assert!(geojson(r#"{"type": "Polygon", "coordinates": []}"#).unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_geojson_is_an_error() {
+        assert!(matches!(geojson(r#"{"features": []}"#), Err(Error::Parse { .. })));
+        assert!(matches!(geojson(r#"{"type": "FeatureCollection"}"#), Err(Error::Parse { .. })));
+        assert!(matches!(geojson(r#"{"type": "Point", "coordinates": ["a", 1]}"#), Err(Error::Parse { .. })));
+        assert!(matches!(geojson(r#"{"type": "Point"}"#), Err(Error::Parse { .. })));
+        assert!(matches!(geojson("[1, 2"), Err(Error::Parse { .. })));
+    }
+}
@@ -0,0 +1,371 @@
+use std::fmt;
+use std::fs;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use genetic::{tsplib, City, Crossover, Error, ExportFormat, Mating, Mutation, Replacement, Selection, TourType};
+
+pub const USAGE: &str = "\
+usage: genetic-rust [solve] [options]
+       genetic-rust bench [options]
+       genetic-rust inspect <file>
+       genetic-rust self-test
+
+Without --input the built-in nine-city example is solved. `inspect` prints
+size, bounding box, duplicates, a nearest-neighbour tour length, the MST lower
+bound and distance matrix memory for an instance without running.
+`bench` solves the instance --runs times with every combination of the
+--selections, --crossovers and --mutations lists (comma-separated, all
+crossover and mutation operators by default) and reports the mean and best
+tour length and the runtime of each; the other options apply to every run.
+`self-test` checks the operators, solves seeded problems with known optima
+and measures evaluation throughput.
+
+options:
+    --input <file>           cities as `x,y` lines (CSV, optionally with a
+                             header and name column), a .geojson file of
+                             points or a TSPLIB .tsp file
+    --population <n>         population size (default 100)
+    --generations <n>        number of generations (default 100)
+    --crossover-rate <r>     share of the population that breeds (default 0.8)
+    --mutation-rate <r>      per-individual mutation probability (default 0.001)
+    --survival-rate <r>      share of breeders kept unchanged (default 0.2)
+    --adaptive-mutation <min,max,target>
+                             vary the mutation rate between min and max,
+                             rising as diversity drops below target
+    --elitism <n>            fittest individuals kept unchanged each generation (default 0)
+    --selection <s>          truncation, tournament:<size> to pick each
+                             parent from random samples, or fuss to pick
+                             uniformly over fitness values (default
+                             truncation)
+    --mating <m>             random, avoid-self, avoid-identical, or
+                             dissimilar:<k> to pair each mother with the
+                             most distant of k partners (default random)
+    --steady-state <n>       breed n offspring per step and replace the
+                             weakest members instead of whole generations
+    --oversampling <f>       breed f times as many offspring as slots and
+                             keep the fittest (default 1, no oversampling)
+    --crossover <op>         single-point, ox, pmx or cx (default single-point)
+    --mutation <op>          swap, inversion, insertion or scramble (default swap)
+    --local-search-rate <r>  share of offspring improved by 2-opt and Or-opt
+                             each generation (default 0)
+    --metric <m>             euclidean, manhattan, haversine (x = latitude,
+                             y = longitude in degrees) or tsplib (the input
+                             file's own EDGE_WEIGHT_TYPE) (default euclidean)
+    --tour <type>            closed (return to the start) or open (default closed)
+    --seed <n>               seed both random sources for a reproducible run;
+                             each generation's derived seed is logged
+    --threads <n>            worker threads for breeding (default 1)
+    --checkpoint <file>      save the run's state to a file when it finishes
+    --resume <file>          continue from a checkpoint; its cities, rates and
+                             tour type replace --input and the GA parameters
+    --stats <file>           write per-generation statistics to a CSV file
+    --svg <file>             draw the best tour as an SVG image
+    --export <file>          write the best tour and fitness history to a
+                             .json or .csv file
+    --progress <n>           print generation stats every n generations
+    --auto-mutation          pick the mutation rate from short pilot runs
+    -h, --help               print this message
+
+bench options:
+    --runs <n>               runs per configuration (default 5); with --seed,
+                             run i of every configuration uses seed + i
+    --selections <list>      selection schemes to compare (default --selection)
+    --crossovers <list>      crossover operators to compare
+    --mutations <list>       mutation operators to compare";
+
+#[derive(Clone)]
+pub struct SolveOptions {
+    pub input: Option<PathBuf>,
+    pub population: usize,
+    pub generations: usize,
+    pub crossover_rate: f64,
+    pub mutation_rate: f64,
+    pub survival_rate: f64,
+    pub elitism: usize,
+    pub adaptive_mutation: Option<(f64, f64, f64)>,
+    pub selection: Selection,
+    pub mating: Mating,
+    pub oversampling: f64,
+    pub replacement: Replacement,
+    pub crossover: Crossover,
+    pub mutation: Mutation,
+    pub local_search_rate: f64,
+    pub metric: Metric,
+    pub tour_type: TourType,
+    pub seed: Option<u64>,
+    pub threads: usize,
+    pub checkpoint: Option<PathBuf>,
+    pub resume: Option<PathBuf>,
+    pub stats: Option<PathBuf>,
+    pub export: Option<(PathBuf, ExportFormat)>,
+    pub svg: Option<PathBuf>,
+    pub progress: usize,
+    pub auto_mutation: bool,
+}
+
+impl Default for SolveOptions {
+    fn default() -> SolveOptions {
+        SolveOptions {
+            input: None,
+            population: 100,
+            generations: 100,
+            crossover_rate: 0.8,
+            mutation_rate: 0.001,
+            survival_rate: 0.2,
+            elitism: 0,
+            adaptive_mutation: None,
+            selection: Selection::Truncation,
+            mating: Mating::Random,
+            oversampling: 1.0,
+            replacement: Replacement::Generational,
+            crossover: Crossover::SinglePoint,
+            mutation: Mutation::Swap,
+            local_search_rate: 0.0,
+            metric: Metric::Euclidean,
+            tour_type: TourType::Closed,
+            seed: None,
+            threads: 1,
+            checkpoint: None,
+            resume: None,
+            stats: None,
+            export: None,
+            svg: None,
+            progress: 0,
+            auto_mutation: false,
+        }
+    }
+}
+
+pub struct BenchOptions {
+    pub solve: SolveOptions,
+    pub runs: usize,
+    pub selections: Vec<Selection>,
+    pub crossovers: Vec<Crossover>,
+    pub mutations: Vec<Mutation>,
+}
+
+// One combination of operators compared by `bench`.
+pub struct Trial {
+    pub selection: Selection,
+    pub crossover: Crossover,
+    pub mutation: Mutation,
+}
+
+impl fmt::Display for Trial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let selection = match self.selection {
+            Selection::Truncation => "truncation".to_string(),
+            Selection::Tournament { size } => format!("tournament:{}", size),
+            Selection::FitnessUniform => "fuss".to_string(),
+        };
+        write!(f, "{} {} {}", selection, self.crossover, self.mutation)
+    }
+}
+
+#[derive(Clone)]
+pub enum Metric {
+    Euclidean,
+    Manhattan,
+    Haversine,
+    Tsplib,
+}
+
+pub enum Command {
+    Solve(Box<SolveOptions>),
+    Bench(Box<BenchOptions>),
+    Inspect(PathBuf),
+    SelfTest,
+    Help,
+}
+
+pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut options = SolveOptions::default();
+    let mut bench: Option<BenchOptions> = None;
+    let mut first = true;
+
+    while let Some(arg) = args.next() {
+        if first && arg == "inspect" {
+            let file = args.next().ok_or("inspect needs a file")?;
+            if let Some(extra) = args.next() {
+                return Err(format!("unexpected argument {}", extra));
+            }
+            return Ok(Command::Inspect(PathBuf::from(file)));
+        }
+        if first && arg == "self-test" {
+            if let Some(extra) = args.next() {
+                return Err(format!("unexpected argument {}", extra));
+            }
+            return Ok(Command::SelfTest);
+        }
+        if first && arg == "bench" {
+            bench = Some(BenchOptions {
+                solve: SolveOptions::default(),
+                runs: 5,
+                selections: Vec::new(),
+                crossovers: Crossover::ALL.to_vec(),
+                mutations: Mutation::ALL.to_vec(),
+            });
+            first = false;
+            continue;
+        }
+        if first && arg == "solve" {
+            first = false;
+            continue;
+        }
+        first = false;
+
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline.clone().or_else(|| args.next()).ok_or(format!("{} needs a value", flag))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--input" => options.input = Some(PathBuf::from(value()?)),
+            "--population" => options.population = number(&flag, &value()?)?,
+            "--generations" => options.generations = number(&flag, &value()?)?,
+            "--crossover-rate" => options.crossover_rate = number(&flag, &value()?)?,
+            "--mutation-rate" => options.mutation_rate = number(&flag, &value()?)?,
+            "--survival-rate" => options.survival_rate = number(&flag, &value()?)?,
+            "--elitism" => options.elitism = number(&flag, &value()?)?,
+            "--adaptive-mutation" => {
+                let value = value()?;
+                let bounds = value.split(',').map(|v| number(&flag, v)).collect::<Result<Vec<f64>, String>>()?;
+                match bounds[..] {
+                    [min, max, target] => options.adaptive_mutation = Some((min, max, target)),
+                    _ => return Err(format!("{} expects min,max,target, got {}", flag, value)),
+                }
+            }
+            "--selection" => options.selection = selection(&flag, &value()?)?,
+            "--mating" => {
+                let value = value()?;
+                options.mating = match value.split_once(':') {
+                    None if value == "random" => Mating::Random,
+                    None if value == "avoid-self" => Mating::AvoidSelf,
+                    None if value == "avoid-identical" => Mating::AvoidIdentical,
+                    Some(("dissimilar", k)) => Mating::Dissimilar { candidates: number(&flag, k)? },
+                    _ => return Err(format!("unknown mating policy {}", value)),
+                }
+            }
+            "--steady-state" => {
+                options.replacement = Replacement::SteadyState { offspring: number(&flag, &value()?)? };
+            }
+            "--oversampling" => options.oversampling = number(&flag, &value()?)?,
+            "--crossover" => options.crossover = operator(&value()?)?,
+            "--mutation" => options.mutation = operator(&value()?)?,
+            "--local-search-rate" => options.local_search_rate = number(&flag, &value()?)?,
+            "--metric" => {
+                options.metric = match value()?.as_str() {
+                    "euclidean" => Metric::Euclidean,
+                    "manhattan" => Metric::Manhattan,
+                    "haversine" => Metric::Haversine,
+                    "tsplib" => Metric::Tsplib,
+                    other => return Err(format!("unknown metric {}", other)),
+                }
+            }
+            "--tour" => {
+                options.tour_type = match value()?.as_str() {
+                    "closed" => TourType::Closed,
+                    "open" => TourType::Open,
+                    other => return Err(format!("unknown tour type {}", other)),
+                }
+            }
+            "--seed" => options.seed = Some(number(&flag, &value()?)?),
+            "--threads" => options.threads = number(&flag, &value()?)?,
+            "--checkpoint" => options.checkpoint = Some(PathBuf::from(value()?)),
+            "--resume" => options.resume = Some(PathBuf::from(value()?)),
+            "--stats" => options.stats = Some(PathBuf::from(value()?)),
+            "--svg" => options.svg = Some(PathBuf::from(value()?)),
+            "--export" => {
+                let file = PathBuf::from(value()?);
+                let format = match file.extension().and_then(|ext| ext.to_str()) {
+                    Some("json") => ExportFormat::Json,
+                    Some("csv") => ExportFormat::Csv,
+                    _ => return Err(format!("{} needs a .json or .csv file", flag)),
+                };
+                options.export = Some((file, format));
+            }
+            "--progress" => options.progress = number(&flag, &value()?)?,
+            "--auto-mutation" => options.auto_mutation = true,
+            "--runs" | "--selections" | "--crossovers" | "--mutations" => {
+                let bench = match bench.as_mut() {
+                    Some(bench) => bench,
+                    None => return Err(format!("{} can only be used with bench", flag)),
+                };
+                let value = value()?;
+                let list = value.split(',');
+                match flag.as_str() {
+                    "--runs" => bench.runs = number(&flag, &value)?,
+                    "--selections" => bench.selections = list.map(|v| selection(&flag, v)).collect::<Result<_, _>>()?,
+                    "--crossovers" => bench.crossovers = list.map(operator).collect::<Result<_, _>>()?,
+                    _ => bench.mutations = list.map(operator).collect::<Result<_, _>>()?,
+                }
+            }
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+
+    let tsp_input = options.input.as_ref().is_some_and(|file| file.extension().is_some_and(|ext| ext == "tsp"));
+    if let (Metric::Tsplib, false) = (&options.metric, tsp_input) {
+        return Err("--metric tsplib needs a TSPLIB .tsp --input".to_string());
+    }
+
+    match bench {
+        Some(mut bench) => {
+            let unsupported = [
+                ("--resume", options.resume.is_some()),
+                ("--checkpoint", options.checkpoint.is_some()),
+                ("--stats", options.stats.is_some()),
+                ("--svg", options.svg.is_some()),
+                ("--export", options.export.is_some()),
+                ("--auto-mutation", options.auto_mutation),
+            ];
+            if let Some((flag, _)) = unsupported.iter().find(|(_, set)| *set) {
+                return Err(format!("{} cannot be used with bench", flag));
+            }
+            if bench.selections.is_empty() {
+                bench.selections.push(options.selection);
+            }
+            bench.solve = options;
+            Ok(Command::Bench(Box::new(bench)))
+        }
+        None => Ok(Command::Solve(Box::new(options))),
+    }
+}
+
+fn selection(flag: &str, value: &str) -> Result<Selection, String> {
+    match value.split_once(':') {
+        None if value == "truncation" => Ok(Selection::Truncation),
+        None if value == "fuss" => Ok(Selection::FitnessUniform),
+        Some(("tournament", size)) => Ok(Selection::Tournament { size: number(flag, size)? }),
+        _ => Err(format!("unknown selection {}", value)),
+    }
+}
+
+// An operator by the name its FromStr accepts.
+fn operator<T: std::str::FromStr<Err = Error>>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|err: Error| err.to_string())
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{} expects a number, got {}", flag, value))
+}
+
+// TSPLIB and GeoJSON files by extension, otherwise CSV as read by
+// City::from_csv.
+pub fn read_cities(file: &PathBuf) -> Result<Vec<City>, Error> {
+    if file.extension().is_some_and(|ext| ext == "tsp") {
+        return Ok(tsplib::load_tsp(file)?.cities);
+    }
+
+    let reader = BufReader::new(fs::File::open(file)?);
+    if file.extension().is_some_and(|ext| ext == "geojson" || ext == "json") {
+        City::from_geojson(reader)
+    } else {
+        City::from_csv(reader)
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::chromosome::Chromosome;
+use crate::error::Error;
+use crate::simulation::Simulation;
+
+// How one configuration fared over its runs in compare. Runtime covers
+// Simulation::run only, not building the simulation.
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub label: String,
+    pub runs: usize,
+    pub mean_cost: f64,
+    pub best_cost: f64,
+    pub std_dev: f64,
+    pub mean_runtime: Duration,
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: mean cost {:.2}, best cost {:.2}, std dev {:.2}, mean runtime {:.3}s over {} runs",
+            self.label,
+            self.mean_cost,
+            self.best_cost,
+            self.std_dev,
+            self.mean_runtime.as_secs_f64(),
+            self.runs
+        )
+    }
+}
+
+// Runs every configuration `runs` times, each run built by `make` from the
+// configuration and the run index, and summarises the costs and runtimes
+// per configuration, in the order given. Configurations are labelled by
+// their Display. Giving run i of every configuration the same seed compares
+// them on equal terms.
+pub fn compare<T, C, R, F>(configurations: &[T], runs: usize, mut make: F) -> Result<Vec<Comparison>, Error>
+where
+    T: fmt::Display,
+    C: Chromosome,
+    R: Rng,
+    F: FnMut(&T, usize) -> Result<Simulation<C, R>, Error>,
+{
+    if runs == 0 {
+        return Err(Error::InvalidParameter { name: "runs", value: 0.0 });
+    }
+
+    let mut comparisons = Vec::with_capacity(configurations.len());
+    for configuration in configurations {
+        let mut costs = Vec::with_capacity(runs);
+        let mut runtime = Duration::ZERO;
+        for run in 0..runs {
+            let mut sim = make(configuration, run)?;
+            let started = Instant::now();
+            costs.push(sim.run().cost);
+            runtime += started.elapsed();
+        }
+
+        let mean_cost = costs.iter().sum::<f64>() / runs as f64;
+        let variance = costs.iter().map(|c| (c - mean_cost).powi(2)).sum::<f64>() / runs as f64;
+        comparisons.push(Comparison {
+            label: configuration.to_string(),
+            runs,
+            mean_cost,
+            best_cost: costs.iter().cloned().fold(f64::INFINITY, f64::min),
+            std_dev: variance.sqrt(),
+            mean_runtime: runtime / runs as u32,
+        });
+    }
+
+    Ok(comparisons)
+}
@@ -0,0 +1,233 @@
+use rand::Rng;
+use rand::distributions::Uniform;
+use rand::seq::SliceRandom;
+
+use crate::error::Error;
+use crate::integer::{IntegerGenome, IntegerProblem};
+use crate::mixed::{Gene, GeneSpec, MixedGenome, MixedProblem};
+
+// Fresh attempts per genome before sampling gives up. Constraints are fixed
+// one variable at a time, which only fails when several constraints
+// interact, so a few attempts are normally plenty.
+const ATTEMPTS: usize = 100;
+
+// sum(coefficients[i] * x[i]) <= limit, e.g. a knapsack's weight limit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearConstraint {
+    pub coefficients: Vec<f64>,
+    pub limit: f64,
+}
+
+impl LinearConstraint {
+    pub fn new(coefficients: Vec<f64>, limit: f64) -> LinearConstraint {
+        LinearConstraint { coefficients, limit }
+    }
+
+    pub fn is_satisfied(&self, values: &[f64]) -> bool {
+        self.coefficients.iter().zip(values).map(|(c, x)| c * x).sum::<f64>() <= self.limit
+    }
+}
+
+// A decision variable as the sampler sees it.
+struct Variable {
+    low: f64,
+    high: f64,
+    integer: bool,
+}
+
+// Draws each variable in random order from the part of its range that still
+// lets every constraint be met with the remaining variables at their most
+// favourable bound. Returns None when a variable's feasible range is empty.
+// The arithmetic is in f64, so integers beyond 2^53 are rounded to a
+// representable neighbour; the callers clamp them back into their bounds.
+fn sample<R: Rng>(variables: &[Variable], constraints: &[LinearConstraint], rng: &mut R) -> Option<Vec<f64>> {
+    let least = |c: f64, v: &Variable| (c * v.low).min(c * v.high);
+
+    let mut values: Vec<f64> = variables.iter().map(|v| v.low).collect();
+    let mut fixed = vec![false; variables.len()];
+    let mut order: Vec<usize> = (0..variables.len()).collect();
+    order.shuffle(rng);
+
+    for &i in &order {
+        let (mut low, mut high) = (variables[i].low, variables[i].high);
+        for constraint in constraints {
+            let c = constraint.coefficients[i];
+            let committed: f64 = (0..variables.len())
+                .filter(|&j| j != i)
+                .map(|j| {
+                    let cj = constraint.coefficients[j];
+                    if fixed[j] { cj * values[j] } else { least(cj, &variables[j]) }
+                })
+                .sum();
+            let slack = constraint.limit - committed;
+
+            if c > 0.0 {
+                high = high.min(slack / c);
+            } else if c < 0.0 {
+                low = low.max(slack / c);
+            } else if slack < 0.0 {
+                return None;
+            }
+        }
+
+        if variables[i].integer {
+            low = low.ceil();
+            high = high.floor();
+        }
+        if low > high {
+            return None;
+        }
+
+        values[i] = match (variables[i].integer, low < high) {
+            (true, _) => rng.sample(Uniform::new_inclusive(low as i64, high as i64)) as f64,
+            (false, true) => rng.gen_range(low, high),
+            (false, false) => low,
+        };
+        fixed[i] = true;
+    }
+
+    Some(values)
+}
+
+fn check_lengths(constraints: &[LinearConstraint], genes: usize) -> Result<(), Error> {
+    match constraints.iter().find(|c| c.coefficients.len() != genes) {
+        Some(c) => Err(Error::LengthMismatch { expected: genes, found: c.coefficients.len() }),
+        None => Ok(()),
+    }
+}
+
+// `count` integer genomes that all satisfy `constraints`, sampled directly
+// instead of penalising infeasible ones. With bounds of (0, 1) this covers
+// bitstring problems such as the 0/1 knapsack.
+pub fn feasible_integer_population<R: Rng>(
+    problem: &IntegerProblem,
+    constraints: &[LinearConstraint],
+    count: usize,
+    rng: &mut R,
+) -> Result<Vec<IntegerGenome>, Error> {
+    check_lengths(constraints, problem.bounds().len())?;
+    let variables: Vec<Variable> = problem.bounds().iter()
+        .map(|&(low, high)| Variable { low: low as f64, high: high as f64, integer: true })
+        .collect();
+
+    (0..count).map(|_| {
+        let values = (0..ATTEMPTS)
+            .find_map(|_| sample(&variables, constraints, rng))
+            .ok_or(Error::Infeasible { attempts: ATTEMPTS })?;
+        let genes = values.into_iter().zip(problem.bounds())
+            .map(|(v, &(low, high))| (v as i64).clamp(low, high))
+            .collect();
+        IntegerGenome::new(genes, problem)
+    }).collect()
+}
+
+// `count` mixed genomes whose real and integer genes satisfy `constraints`.
+// Coefficients of categorical genes are ignored; those genes are drawn
+// uniformly from their domains.
+pub fn feasible_mixed_population<R: Rng>(
+    problem: &MixedProblem,
+    constraints: &[LinearConstraint],
+    count: usize,
+    rng: &mut R,
+) -> Result<Vec<MixedGenome>, Error> {
+    check_lengths(constraints, problem.specs().len())?;
+    let variables: Vec<Variable> = problem.specs().iter().map(|spec| match *spec {
+        GeneSpec::Real { low, high, .. } => Variable { low, high, integer: false },
+        GeneSpec::Integer { low, high } => Variable { low: low as f64, high: high as f64, integer: true },
+        GeneSpec::Categorical { .. } => Variable { low: 0.0, high: 0.0, integer: true },
+    }).collect();
+
+    (0..count).map(|_| {
+        let values = (0..ATTEMPTS)
+            .find_map(|_| sample(&variables, constraints, rng))
+            .ok_or(Error::Infeasible { attempts: ATTEMPTS })?;
+        let genes = values.into_iter().zip(problem.specs()).map(|(value, spec)| match spec {
+            GeneSpec::Real { .. } => Gene::Real(value),
+            &GeneSpec::Integer { low, high } => Gene::Integer((value as i64).clamp(low, high)),
+            GeneSpec::Categorical { domain } => Gene::Categorical(rng.gen_range(0, domain.len())),
+        }).collect();
+        MixedGenome::new(genes, problem)
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use super::*;
+
+    fn knapsack() -> (IntegerProblem, Vec<LinearConstraint>) {
+        let problem = IntegerProblem::new(vec![(0, 1); 6], |genes| genes.iter().sum::<i64>() as f64).unwrap();
+        let constraints = vec![
+            LinearConstraint::new(vec![3.0, 4.0, 5.0, 2.0, 6.0, 1.0], 10.0),
+            LinearConstraint::new(vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0], 3.0),
+        ];
+        (problem, constraints)
+    }
+
+    fn values(genome: &IntegerGenome) -> Vec<f64> {
+        genome.genes().iter().map(|&g| g as f64).collect()
+    }
+
+    #[test]
+    fn every_sampled_knapsack_is_feasible() {
+        let (problem, constraints) = knapsack();
+        let mut rng = SmallRng::seed_from_u64(11);
+        let population = feasible_integer_population(&problem, &constraints, 200, &mut rng).unwrap();
+        assert_eq!(population.len(), 200);
+        for genome in &population {
+            assert!(constraints.iter().all(|c| c.is_satisfied(&values(genome))));
+        }
+    }
+
+    #[test]
+    fn mixed_genes_satisfy_the_constraints() {
+        let specs = vec![
+            GeneSpec::Real { low: 0.0, high: 10.0, sigma: 1.0 },
+            GeneSpec::Integer { low: -5, high: 5 },
+        ];
+        let problem = MixedProblem::new(specs, |_| 1.0).unwrap();
+        let constraints = vec![LinearConstraint::new(vec![1.0, 2.0], 4.0)];
+        let mut rng = SmallRng::seed_from_u64(5);
+        for genome in feasible_mixed_population(&problem, &constraints, 100, &mut rng).unwrap() {
+            let values: Vec<f64> = genome.genes().iter().map(|g| match *g {
+                Gene::Real(v) => v,
+                Gene::Integer(v) => v as f64,
+                Gene::Categorical(_) => 0.0,
+            }).collect();
+            assert!(constraints[0].is_satisfied(&values));
+        }
+    }
+
+    #[test]
+    fn infeasible_constraints_yield_none_and_an_error() {
+        let variables = vec![Variable { low: 1.0, high: 2.0, integer: true }];
+        let constraints = vec![LinearConstraint::new(vec![1.0], 0.5)];
+        let mut rng = SmallRng::seed_from_u64(1);
+        assert_eq!(sample(&variables, &constraints, &mut rng), None);
+
+        let problem = IntegerProblem::new(vec![(1, 2)], |_| 1.0).unwrap();
+        let result = feasible_integer_population(&problem, &constraints, 1, &mut rng);
+        assert!(matches!(result, Err(Error::Infeasible { attempts: ATTEMPTS })));
+    }
+
+    #[test]
+    fn coefficient_count_must_match_the_genes() {
+        let (problem, _) = knapsack();
+        let constraints = vec![LinearConstraint::new(vec![1.0, 2.0], 3.0)];
+        let mut rng = SmallRng::seed_from_u64(1);
+        let result = feasible_integer_population(&problem, &constraints, 1, &mut rng);
+        assert!(matches!(result, Err(Error::LengthMismatch { expected: 6, found: 2 })));
+    }
+
+    #[test]
+    fn bounds_at_the_edge_of_i64_do_not_overflow() {
+        let problem = IntegerProblem::new(vec![(i64::MAX - 10, i64::MAX)], |_| 1.0).unwrap();
+        let constraints = vec![LinearConstraint::new(vec![0.0], 0.0)];
+        let mut rng = SmallRng::seed_from_u64(2);
+        for genome in feasible_integer_population(&problem, &constraints, 20, &mut rng).unwrap() {
+            assert!(genome.genes()[0] >= i64::MAX - 10);
+        }
+    }
+}
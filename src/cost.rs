@@ -0,0 +1,194 @@
+use crate::city::City;
+use crate::matrix::DistanceMatrix;
+
+// How edge lengths are accumulated into a tour cost. Integer mode rounds each
+// scaled edge to an i64 and sums exactly, so costs compare identically on
+// every platform and naturally integral instances lose nothing.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CostMode {
+    #[default]
+    Float,
+    Integer { scale: f64 },
+}
+
+// What a tour costs. Duration converts each leg to travel time at `speed`
+// and adds the service time of every city visited.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Objective {
+    #[default]
+    Distance,
+    Duration { speed: f64 },
+}
+
+// Whether a tour returns to its first city. Closed is the classic TSP; Open
+// costs a path that ends at its last city.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TourType {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CostModel {
+    pub mode: CostMode,
+    pub objective: Objective,
+    pub tour_type: TourType,
+}
+
+impl CostModel {
+    pub(crate) fn tour_cost(self, path: &[usize], city_list: &[City], matrix: &DistanceMatrix) -> f64 {
+        let visited = visited_cities(path, city_list, matrix, self);
+        let path = visited.as_ref().map_or(path, |v| &v[..]);
+
+        let legs = path.windows(2).map(|w| self.leg(city_list, matrix, w[0], w[1]));
+        // The return leg only travels; the first city was served at the start.
+        let closing = match (self.tour_type, path.first(), path.last()) {
+            (TourType::Closed, Some(&first), Some(&last)) if path.len() > 1 => {
+                self.travel(matrix.distance(last, first))
+            }
+            _ => 0.0,
+        };
+        let start = match (self.objective, path.first()) {
+            (Objective::Duration { .. }, Some(&first)) => city_list[first].service_time,
+            _ => 0.0,
+        };
+        // Skipped optional cities forfeit their reward.
+        let forfeited = visited.as_ref().map_or(0.0, |v| {
+            let mut kept = vec![false; city_list.len()];
+            for &city in v {
+                kept[city] = true;
+            }
+            city_list.iter().zip(kept)
+                .filter(|(_, kept)| !kept)
+                .filter_map(|(c, _)| c.reward)
+                .sum()
+        });
+        let edges = std::iter::once(start)
+            .chain(legs)
+            .chain(std::iter::once(closing))
+            .chain(std::iter::once(forfeited));
+
+        match self.mode {
+            CostMode::Float => edges.sum(),
+            CostMode::Integer { scale } => {
+                edges.map(|d| (d * scale).round() as i64).sum::<i64>() as f64
+            }
+        }
+    }
+
+    fn leg(self, city_list: &[City], matrix: &DistanceMatrix, a: usize, b: usize) -> f64 {
+        let service = match self.objective {
+            Objective::Distance => 0.0,
+            Objective::Duration { .. } => city_list[b].service_time,
+        };
+        self.travel(matrix.distance(a, b)) + service
+    }
+
+    fn travel(self, distance: f64) -> f64 {
+        match self.objective {
+            Objective::Distance => distance,
+            Objective::Duration { speed } => distance / speed,
+        }
+    }
+}
+
+// Decodes a permutation into the cities actually visited. Mandatory cities
+// are always kept; an optional city is kept only when the detour it adds
+// between its neighbours in the permutation costs less than its reward. On a
+// closed tour the last city's next neighbour is the first city kept.
+// Returns None when every city is mandatory, so plain TSP pays nothing extra.
+pub(crate) fn visited_cities(
+    path: &[usize],
+    city_list: &[City],
+    matrix: &DistanceMatrix,
+    cost_model: CostModel,
+) -> Option<Vec<usize>> {
+    if city_list.iter().all(|c| c.reward.is_none()) {
+        return None;
+    }
+
+    let mut visited: Vec<usize> = Vec::with_capacity(path.len());
+    for (i, &city) in path.iter().enumerate() {
+        let reward = match city_list[city].reward {
+            None => {
+                visited.push(city);
+                continue;
+            }
+            Some(reward) => reward,
+        };
+
+        let leg = |a, b| cost_model.leg(city_list, matrix, a, b);
+        let next = match (path.get(i + 1), cost_model.tour_type) {
+            (None, TourType::Closed) => visited.first(),
+            (next, _) => next,
+        };
+        let detour = match (visited.last(), next) {
+            (Some(&prev), Some(&next)) => leg(prev, city) + leg(city, next) - leg(prev, next),
+            (Some(&prev), None) => leg(prev, city),
+            (None, Some(&next)) => leg(city, next),
+            (None, None) => 0.0,
+        };
+
+        if detour < reward {
+            visited.push(city);
+        }
+    }
+
+    Some(visited)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Vec<City> {
+        vec![City::new(0.0, 0.0), City::new(0.0, 1.0), City::new(1.0, 1.0), City::new(1.0, 0.0)]
+    }
+
+    fn cost(model: CostModel, path: &[usize], cities: &[City]) -> f64 {
+        model.tour_cost(path, cities, &DistanceMatrix::new(cities))
+    }
+
+    #[test]
+    fn closed_tour_includes_the_return_edge() {
+        let model = CostModel::default();
+        assert_eq!(model.tour_type, TourType::Closed);
+        assert_eq!(cost(model, &[0, 1, 2, 3], &square()), 4.0);
+    }
+
+    #[test]
+    fn open_tour_ends_at_the_last_city() {
+        let model = CostModel { tour_type: TourType::Open, ..CostModel::default() };
+        assert_eq!(cost(model, &[0, 1, 2, 3], &square()), 3.0);
+    }
+
+    #[test]
+    fn closed_tour_cost_does_not_depend_on_the_starting_city() {
+        let cities = square();
+        let model = CostModel::default();
+        assert_eq!(cost(model, &[0, 2, 1, 3], &cities), cost(model, &[1, 3, 0, 2], &cities));
+    }
+
+    #[test]
+    fn single_city_costs_nothing_in_either_mode() {
+        let cities = vec![City::new(2.0, 3.0)];
+        for &tour_type in &[TourType::Closed, TourType::Open] {
+            let model = CostModel { tour_type, ..CostModel::default() };
+            assert_eq!(cost(model, &[0], &cities), 0.0);
+        }
+    }
+
+    #[test]
+    fn return_leg_adds_travel_time_but_no_service() {
+        let cities = vec![
+            City::with_service_time(0.0, 0.0, 1.0),
+            City::with_service_time(0.0, 2.0, 1.0),
+        ];
+        let closed = CostModel { objective: Objective::Duration { speed: 2.0 }, ..CostModel::default() };
+        let open = CostModel { tour_type: TourType::Open, ..closed };
+
+        assert_eq!(cost(open, &[0, 1], &cities), 3.0);
+        assert_eq!(cost(closed, &[0, 1], &cities), 4.0);
+    }
+}
@@ -0,0 +1,321 @@
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use rand::{Rng, RngCore};
+use rand::distributions::{Distribution, Uniform};
+
+use crate::error::Error;
+
+// Combines two parent permutations into a child permutation. Operators are
+// shared between threads and cloned simulations, hence Send + Sync, and take
+// a type-erased RNG so they can be stored as trait objects.
+pub trait CrossoverOperator: Send + Sync {
+    fn crossover(&self, mother: &[usize], father: &[usize], rng: &mut dyn RngCore) -> Vec<usize>;
+}
+
+// Mother's prefix up to a random cut, then the remaining cities in father's
+// order. Cheap, but breaks most of the father's edges.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SinglePoint;
+
+impl CrossoverOperator for SinglePoint {
+    fn crossover(&self, mother: &[usize], father: &[usize], rng: &mut dyn RngCore) -> Vec<usize> {
+        if mother.is_empty() {
+            return Vec::new();
+        }
+
+        let crossover_point = Uniform::new(0, mother.len()).sample(rng);
+
+        let mother_dna = &mother[0..crossover_point];
+        let mut father_dna: Vec<usize> = father.iter().filter_map(|d| {
+            if !mother_dna.contains(d) {
+                return Some(*d)
+            }
+            None
+        }).collect();
+
+        let mut child = Vec::new();
+        child.extend_from_slice(mother_dna);
+        child.append(&mut father_dna);
+
+        child
+    }
+}
+
+// OX1: a random slice of the mother is kept in place and the remaining
+// positions, starting after the slice and wrapping around, are filled with the
+// missing cities in the order they follow the slice in the father.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OrderCrossover;
+
+impl CrossoverOperator for OrderCrossover {
+    fn crossover(&self, mother: &[usize], father: &[usize], rng: &mut dyn RngCore) -> Vec<usize> {
+        let n = mother.len();
+        if n < 2 {
+            return mother.to_vec();
+        }
+
+        let (start, end) = cut_points(n, rng);
+        order_crossover(mother, father, start..end + 1)
+    }
+}
+
+// OX1 with the mother's slice fixed; an empty slice starts filling at its
+// position.
+fn order_crossover(mother: &[usize], father: &[usize], slice: Range<usize>) -> Vec<usize> {
+    let n = mother.len();
+    let mut taken = vec![false; n];
+    let mut child = vec![0; n];
+    for i in slice.clone() {
+        child[i] = mother[i];
+        taken[mother[i]] = true;
+    }
+
+    let mut slot = slice.end % n;
+    for offset in 0..n {
+        let city = father[(slice.end + offset) % n];
+        if !taken[city] {
+            child[slot] = city;
+            slot = (slot + 1) % n;
+        }
+    }
+
+    child
+}
+
+// PMX: a random slice of the mother is kept in place; the father's cities
+// displaced from that slice are relocated by following the slice's
+// position-wise mapping, and every other position is copied from the father.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PartiallyMapped;
+
+impl CrossoverOperator for PartiallyMapped {
+    fn crossover(&self, mother: &[usize], father: &[usize], rng: &mut dyn RngCore) -> Vec<usize> {
+        let n = mother.len();
+        if n < 2 {
+            return mother.to_vec();
+        }
+
+        let (start, end) = cut_points(n, rng);
+        partially_mapped(mother, father, start..end + 1)
+    }
+}
+
+fn partially_mapped(mother: &[usize], father: &[usize], slice: Range<usize>) -> Vec<usize> {
+    let n = mother.len();
+    let father_position = positions(father);
+    let mut in_slice = vec![false; n];
+    let mut child: Vec<Option<usize>> = vec![None; n];
+    for i in slice.clone() {
+        child[i] = Some(mother[i]);
+        in_slice[mother[i]] = true;
+    }
+
+    for (i, &city) in father.iter().enumerate().take(slice.end).skip(slice.start) {
+        if in_slice[city] {
+            continue;
+        }
+
+        let mut j = i;
+        while slice.contains(&j) {
+            j = father_position[mother[j]];
+        }
+        child[j] = Some(city);
+    }
+
+    child.into_iter().zip(father).map(|(c, &f)| c.unwrap_or(f)).collect()
+}
+
+// CX: positions are partitioned into cycles between the parents; alternate
+// cycles are copied from the mother and the father, so every city keeps the
+// position it held in one of the parents.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CycleCrossover;
+
+impl CrossoverOperator for CycleCrossover {
+    fn crossover(&self, mother: &[usize], father: &[usize], _rng: &mut dyn RngCore) -> Vec<usize> {
+        let n = mother.len();
+        let mother_position = positions(mother);
+        let mut assigned = vec![false; n];
+        let mut child = vec![0; n];
+        let mut from_mother = true;
+
+        for start in 0..n {
+            if assigned[start] {
+                continue;
+            }
+
+            let mut i = start;
+            loop {
+                child[i] = if from_mother { mother[i] } else { father[i] };
+                assigned[i] = true;
+                i = mother_position[father[i]];
+                if i == start {
+                    break;
+                }
+            }
+            from_mother = !from_mother;
+        }
+
+        child
+    }
+}
+
+// The built-in operators by name, for configuring a run from text. Each
+// variant crosses over as its operator does.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Crossover {
+    SinglePoint,
+    Order,
+    PartiallyMapped,
+    Cycle,
+}
+
+impl Crossover {
+    pub const ALL: [Crossover; 4] = [Crossover::SinglePoint, Crossover::Order, Crossover::PartiallyMapped, Crossover::Cycle];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Crossover::SinglePoint => "single-point",
+            Crossover::Order => "ox",
+            Crossover::PartiallyMapped => "pmx",
+            Crossover::Cycle => "cx",
+        }
+    }
+}
+
+impl CrossoverOperator for Crossover {
+    fn crossover(&self, mother: &[usize], father: &[usize], rng: &mut dyn RngCore) -> Vec<usize> {
+        match self {
+            Crossover::SinglePoint => SinglePoint.crossover(mother, father, rng),
+            Crossover::Order => OrderCrossover.crossover(mother, father, rng),
+            Crossover::PartiallyMapped => PartiallyMapped.crossover(mother, father, rng),
+            Crossover::Cycle => CycleCrossover.crossover(mother, father, rng),
+        }
+    }
+}
+
+impl fmt::Display for Crossover {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Crossover {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Crossover, Error> {
+        Crossover::ALL.iter().copied()
+            .find(|operator| operator.name() == name)
+            .ok_or_else(|| Error::UnknownName { kind: "crossover operator", name: name.to_string() })
+    }
+}
+
+fn cut_points(n: usize, rng: &mut dyn RngCore) -> (usize, usize) {
+    let a = rng.gen_range(0, n);
+    let b = rng.gen_range(0, n);
+    (a.min(b), a.max(b))
+}
+
+fn positions(order: &[usize]) -> Vec<usize> {
+    let mut position = vec![0; order.len()];
+    for (i, &city) in order.iter().enumerate() {
+        position[city] = i;
+    }
+    position
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+    use rand::seq::SliceRandom;
+
+    use super::*;
+
+    fn is_permutation(order: &[usize]) -> bool {
+        let mut sorted = order.to_vec();
+        sorted.sort_unstable();
+        sorted.into_iter().eq(0..order.len())
+    }
+
+    // The 1-based textbook parents, shifted to 0-based cities.
+    fn textbook() -> (Vec<usize>, Vec<usize>) {
+        (vec![0, 1, 2, 3, 4, 5, 6, 7, 8], vec![3, 4, 1, 0, 7, 6, 5, 8, 2])
+    }
+
+    #[test]
+    fn children_are_permutations() {
+        let operators: [&dyn CrossoverOperator; 4] = [&SinglePoint, &OrderCrossover, &PartiallyMapped, &CycleCrossover];
+        let mut rng = SmallRng::seed_from_u64(3);
+        for n in 0..12 {
+            for _ in 0..50 {
+                let mut mother: Vec<usize> = (0..n).collect();
+                let mut father = mother.clone();
+                mother.shuffle(&mut rng);
+                father.shuffle(&mut rng);
+                for operator in &operators {
+                    let child = operator.crossover(&mother, &father, &mut rng);
+                    assert!(is_permutation(&child), "{:?} x {:?} gave {:?}", mother, father, child);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parents_of_length_one_and_two_are_handled() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        for operator in [&OrderCrossover as &dyn CrossoverOperator, &PartiallyMapped, &CycleCrossover] {
+            assert_eq!(operator.crossover(&[0], &[0], &mut rng), vec![0]);
+            for _ in 0..20 {
+                let child = operator.crossover(&[0, 1], &[1, 0], &mut rng);
+                assert!(child == vec![0, 1] || child == vec![1, 0]);
+            }
+        }
+    }
+
+    #[test]
+    fn slices_of_length_zero_and_one() {
+        let (mother, father) = textbook();
+        for start in 0..mother.len() {
+            assert!(is_permutation(&order_crossover(&mother, &father, start..start)));
+            assert_eq!(partially_mapped(&mother, &father, start..start), father);
+
+            let child = order_crossover(&mother, &father, start..start + 1);
+            assert!(is_permutation(&child));
+            assert_eq!(child[start], mother[start]);
+            let child = partially_mapped(&mother, &father, start..start + 1);
+            assert!(is_permutation(&child));
+            assert_eq!(child[start], mother[start]);
+        }
+    }
+
+    #[test]
+    fn order_crossover_matches_the_textbook_example() {
+        let (mother, father) = textbook();
+        assert_eq!(order_crossover(&mother, &father, 3..7), vec![1, 0, 7, 3, 4, 5, 6, 8, 2]);
+    }
+
+    #[test]
+    fn partially_mapped_matches_the_textbook_example() {
+        let (mother, father) = textbook();
+        assert_eq!(partially_mapped(&mother, &father, 3..7), vec![0, 7, 1, 3, 4, 5, 6, 8, 2]);
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for &operator in &Crossover::ALL {
+            assert_eq!(operator.to_string().parse::<Crossover>().unwrap(), operator);
+        }
+        assert!(matches!("ox2".parse::<Crossover>(), Err(Error::UnknownName { .. })));
+    }
+
+    #[test]
+    fn cycle_crossover_matches_the_textbook_example() {
+        let mother = vec![0, 1, 2, 3, 4, 5, 6, 7];
+        let father = vec![7, 4, 1, 0, 2, 5, 3, 6];
+        let mut rng = SmallRng::seed_from_u64(1);
+        assert_eq!(CycleCrossover.crossover(&mother, &father, &mut rng), vec![0, 4, 1, 3, 2, 5, 6, 7]);
+    }
+}
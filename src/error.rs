@@ -0,0 +1,70 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    EmptyPopulation,
+    EmptyCityList,
+    // Fewer than the two members breeding needs.
+    PopulationTooSmall { size: usize },
+    InvalidRate { name: &'static str, value: f64 },
+    InvalidParameter { name: &'static str, value: f64 },
+    LengthMismatch { expected: usize, found: usize },
+    InvalidTour(String),
+    Parse { line: usize, reason: String },
+    // No genome satisfying the constraints was found.
+    Infeasible { attempts: usize },
+    // A name, such as an operator's, that matches nothing of its kind.
+    UnknownName { kind: &'static str, name: String },
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::EmptyPopulation => write!(f, "population is empty"),
+            Error::EmptyCityList => write!(f, "city list is empty"),
+            Error::PopulationTooSmall { size } => {
+                write!(f, "population of {} is too small to breed", size)
+            }
+            Error::InvalidRate { name, value } => {
+                write!(f, "{} must be between 0 and 1, got {}", name, value)
+            }
+            Error::InvalidParameter { name, value } => write!(f, "invalid {}: {}", name, value),
+            Error::LengthMismatch { expected, found } => {
+                write!(f, "expected {} values, found {}", expected, found)
+            }
+            Error::InvalidTour(reason) => write!(f, "invalid tour: {}", reason),
+            Error::Parse { line, reason } => write!(f, "line {}: {}", line, reason),
+            Error::Infeasible { attempts } => {
+                write!(f, "no feasible genome found in {} attempts", attempts)
+            }
+            Error::UnknownName { kind, name } => write!(f, "unknown {} {}", kind, name),
+            Error::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+pub(crate) fn check_rate(name: &'static str, value: f64) -> Result<(), Error> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(Error::InvalidRate { name, value })
+    }
+}
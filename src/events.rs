@@ -0,0 +1,64 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::simulation::GenerationStats;
+
+// Lifecycle milestones published to subscribers registered with
+// Simulation::subscribe.
+#[derive(Clone, Debug)]
+pub enum Event {
+    RunStarted { generation: usize },
+    GenerationCompleted(GenerationStats),
+    NewBestFound { generation: usize, best_fitness: f64 },
+    // Sent once when the best fitness has not improved for the configured
+    // number of generations.
+    Stagnation { generation: usize, generations_without_improvement: usize },
+    // Published by IslandModel when island `from` sends `migrants`
+    // individuals to island `to`.
+    MigrationPerformed { generation: usize, from: usize, to: usize, migrants: usize },
+    RunFinished(GenerationStats),
+    // The K fittest individuals, fittest first, as (fitness, description).
+    TopIndividuals { generation: usize, individuals: Vec<(f64, String)> },
+    // The generation time budget changed how many offspring are bred;
+    // `elapsed` is the breeding time of the generation that triggered it.
+    OffspringAdjusted { generation: usize, elapsed: Duration, offspring: usize },
+    // The generation time budget suspended (`enabled` false) or restored
+    // local search.
+    LocalSearchToggled { generation: usize, elapsed: Duration, enabled: bool },
+}
+
+// Where Simulation::with_top_k_log sends its snapshots: as
+// Event::TopIndividuals to subscribers, or as CSV lines
+// (`generation,rank,fitness,individual`) to a writer. Writes are best-effort;
+// a failing writer does not stop the run.
+#[derive(Clone)]
+pub enum TopKSink {
+    Events,
+    Writer(Arc<Mutex<dyn Write + Send>>),
+}
+
+// Subscribers are shared rather than owned so a Simulation stays Clone,
+// Send and Sync.
+pub type Subscriber = Arc<Mutex<dyn FnMut(&Event) + Send>>;
+
+#[derive(Clone, Default)]
+pub(crate) struct Subscribers(Vec<Subscriber>);
+
+impl Subscribers {
+    pub(crate) fn push(&mut self, subscriber: Subscriber) {
+        self.0.push(subscriber);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub(crate) fn publish(&self, event: Event) {
+        for subscriber in &self.0 {
+            if let Ok(mut subscriber) = subscriber.lock() {
+                (*subscriber)(&event);
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::io::Write;
+
+use crate::city::City;
+use crate::error::Error;
+use crate::json::quote;
+use crate::simulation::GenerationStats;
+use crate::stats::{metrics, write_history_csv};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    // One object: `length`, `tour` (index, x, y and any name of every
+    // stop, in order) and `history` (one object per generation).
+    Json,
+    // A `length` line, the tour as `position,city,x,y,name` rows and the
+    // history in the stats CSV layout, separated by blank lines.
+    Csv,
+}
+
+// What Simulation::export_results writes.
+pub(crate) struct Results<'a> {
+    pub(crate) length: f64,
+    pub(crate) tour: Vec<usize>,
+    pub(crate) cities: &'a [City],
+    pub(crate) history: &'a [GenerationStats],
+}
+
+impl Results<'_> {
+    pub(crate) fn write<W: Write>(&self, mut w: W, format: ExportFormat) -> Result<(), Error> {
+        match format {
+            ExportFormat::Json => self.write_json(&mut w),
+            ExportFormat::Csv => self.write_csv(&mut w),
+        }
+    }
+
+    fn write_json<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+        writeln!(w, "{{")?;
+        writeln!(w, "  \"length\": {},", number(self.length))?;
+
+        writeln!(w, "  \"tour\": [")?;
+        for (i, &city) in self.tour.iter().enumerate() {
+            let c = &self.cities[city];
+            let comma = if i + 1 < self.tour.len() { "," } else { "" };
+            let name = c.name.as_ref().map_or(String::new(), |name| format!(", \"name\": {}", quote(name)));
+            writeln!(w, "    {{\"index\": {}, \"x\": {}, \"y\": {}{}}}{}", city, number(c.x), number(c.y), name, comma)?;
+        }
+        writeln!(w, "  ],")?;
+
+        writeln!(w, "  \"history\": [")?;
+        for (i, stats) in self.history.iter().enumerate() {
+            let fields: Vec<String> = metrics(stats).into_iter()
+                .map(|(name, value)| format!("\"{}\": {}", name, number(value)))
+                .collect();
+            let comma = if i + 1 < self.history.len() { "," } else { "" };
+            writeln!(w, "    {{\"generation\": {}, {}}}{}", stats.generation, fields.join(", "), comma)?;
+        }
+        writeln!(w, "  ]")?;
+        writeln!(w, "}}")?;
+        Ok(())
+    }
+
+    fn write_csv<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+        writeln!(w, "length,{}", self.length)?;
+        writeln!(w)?;
+
+        writeln!(w, "position,city,x,y,name")?;
+        for (i, &city) in self.tour.iter().enumerate() {
+            let c = &self.cities[city];
+            let name = c.name.as_deref().map_or(String::new(), csv_field);
+            writeln!(w, "{},{},{},{},{}", i, city, c.x, c.y, name)?;
+        }
+        writeln!(w)?;
+
+        write_history_csv(self.history, w)
+    }
+}
+
+// Quoted, with quotes doubled, when the field would otherwise split.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// JSON has no encoding for NaN or infinity.
+fn number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
@@ -0,0 +1,46 @@
+use std::fs;
+use std::path::Path as FsPath;
+
+use crate::error::Error;
+use crate::path::{is_permutation, Path};
+
+// Tours are stored one per `.tour` file as city indices separated by
+// whitespace, commas or `->` (as in the route Path's Display prints). Lines
+// starting with `#` are comments.
+
+pub fn save_tour(file: &FsPath, path: &Path) -> Result<(), Error> {
+    let order: Vec<String> = path.order().iter().map(|i| i.to_string()).collect();
+    fs::write(file, format!("# cost {}\n{}\n", path.cost(), order.join(" ")))?;
+    Ok(())
+}
+
+pub fn parse_tour(text: &str) -> Option<Vec<usize>> {
+    text.lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ',' || c == '-' || c == '>'))
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse().ok())
+        .collect()
+}
+
+// Loads every `.tour` file in `dir` that is a permutation of `city_count`
+// cities, skipping files for other instances. Files are read in name order
+// so the result does not depend on directory iteration order.
+pub fn load_dir(dir: &FsPath, city_count: usize) -> Result<Vec<Vec<usize>>, Error> {
+    let mut files: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "tour"))
+        .collect();
+    files.sort();
+
+    let mut tours = Vec::new();
+    for file in files {
+        if let Some(order) = parse_tour(&fs::read_to_string(&file)?) {
+            if is_permutation(&order, city_count) {
+                tours.push(order);
+            }
+        }
+    }
+
+    Ok(tours)
+}
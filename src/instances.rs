@@ -0,0 +1,102 @@
+use std::f64::consts::PI;
+
+use rand::Rng;
+use rand::distributions::{Distribution, Exp, Normal, Uniform};
+
+use crate::city::City;
+use crate::error::{check_rate, Error};
+
+// Synthetic instance families with known qualitative structure. Random
+// families draw everything from the supplied RNG, so a seeded RNG gives the
+// same instance every time.
+
+pub fn grid(rows: usize, cols: usize, spacing: f64) -> Vec<City> {
+    let mut cities = Vec::with_capacity(rows * cols);
+
+    for r in 0..rows {
+        for c in 0..cols {
+            cities.push(City::new(c as f64 * spacing, r as f64 * spacing));
+        }
+    }
+
+    cities
+}
+
+pub fn ring(count: usize, radius: f64) -> Vec<City> {
+    (0..count).map(|i| {
+        let angle = 2.0 * PI * i as f64 / count as f64;
+        City::new(radius * angle.cos(), radius * angle.sin())
+    }).collect()
+}
+
+// Gaussian blobs with centres spread uniformly over [0, extent)^2.
+pub fn clustered<R: Rng>(
+    clusters: usize,
+    per_cluster: usize,
+    spread: f64,
+    extent: f64,
+    rng: &mut R,
+) -> Result<Vec<City>, Error> {
+    check_extent(extent)?;
+    if spread.is_nan() || spread < 0.0 {
+        return Err(Error::InvalidParameter { name: "spread", value: spread });
+    }
+
+    let centre = Uniform::new(0.0, extent);
+    let offset = Normal::new(0.0, spread);
+    let mut cities = Vec::with_capacity(clusters * per_cluster);
+
+    for _ in 0..clusters {
+        let cx = centre.sample(rng);
+        let cy = centre.sample(rng);
+        for _ in 0..per_cluster {
+            cities.push(City::new(cx + offset.sample(rng), cy + offset.sample(rng)));
+        }
+    }
+
+    Ok(cities)
+}
+
+// Cities pulled towards a few attraction points with exponentially
+// decaying density, plus a uniform background, in the style of the
+// TSPLIB/DIMACS clustered generators.
+pub fn attraction<R: Rng>(
+    count: usize,
+    attractors: usize,
+    background: f64,
+    extent: f64,
+    rng: &mut R,
+) -> Result<Vec<City>, Error> {
+    check_extent(extent)?;
+    check_rate("background", background)?;
+
+    let coordinate = Uniform::new(0.0, extent);
+    let points: Vec<(f64, f64)> = (0..attractors.max(1))
+        .map(|_| (coordinate.sample(rng), coordinate.sample(rng)))
+        .collect();
+    let pick = Uniform::new(0, points.len());
+    let distance = Exp::new(10.0 / extent);
+    let mut cities = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        if rng.gen_bool(background) {
+            cities.push(City::new(coordinate.sample(rng), coordinate.sample(rng)));
+            continue;
+        }
+
+        let (ax, ay) = points[pick.sample(rng)];
+        let angle = rng.gen_range(0.0, 2.0 * PI);
+        let r = distance.sample(rng);
+        cities.push(City::new(ax + r * angle.cos(), ay + r * angle.sin()));
+    }
+
+    Ok(cities)
+}
+
+fn check_extent(extent: f64) -> Result<(), Error> {
+    if extent > 0.0 && extent.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter { name: "extent", value: extent })
+    }
+}
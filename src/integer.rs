@@ -0,0 +1,195 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
+
+use rand::Rng;
+use rand::distributions::Uniform;
+
+use crate::chromosome::{Chromosome, Distance, Fnv};
+use crate::error::Error;
+
+type Objective = Arc<dyn Fn(&[i64]) -> f64 + Send + Sync>;
+
+// An integer parameter-optimisation problem: inclusive bounds for each
+// decision variable and the fitness to maximise.
+#[derive(Clone)]
+pub struct IntegerProblem {
+    bounds: Vec<(i64, i64)>,
+    objective: Objective,
+}
+
+impl IntegerProblem {
+    pub fn new<F>(bounds: Vec<(i64, i64)>, objective: F) -> Result<IntegerProblem, Error>
+    where
+        F: Fn(&[i64]) -> f64 + Send + Sync + 'static,
+    {
+        if bounds.is_empty() {
+            return Err(Error::InvalidParameter { name: "bounds", value: 0.0 });
+        }
+        if let Some(&(low, _)) = bounds.iter().find(|(low, high)| low > high) {
+            return Err(Error::InvalidParameter { name: "lower bound", value: low as f64 });
+        }
+
+        Ok(IntegerProblem { bounds, objective: Arc::new(objective) })
+    }
+
+    pub fn bounds(&self) -> &[(i64, i64)] {
+        &self.bounds
+    }
+}
+
+// A vector of bounded integers. Mutation flips one bit of a gene's Gray code,
+// so a single flip often moves the value by one step, where a flip in plain
+// binary can jump across half the range.
+#[derive(Clone, Debug)]
+pub struct IntegerGenome {
+    fitness: f64,
+    genes: Vec<i64>,
+}
+
+impl IntegerGenome {
+    pub fn new(genes: Vec<i64>, problem: &IntegerProblem) -> Result<IntegerGenome, Error> {
+        if genes.len() != problem.bounds.len() {
+            return Err(Error::LengthMismatch { expected: problem.bounds.len(), found: genes.len() });
+        }
+        let outside = genes.iter().zip(&problem.bounds).find(|&(gene, &(low, high))| *gene < low || *gene > high);
+        if let Some((&gene, _)) = outside {
+            return Err(Error::InvalidParameter { name: "gene", value: gene as f64 });
+        }
+
+        let fitness = (problem.objective)(&genes);
+        Ok(IntegerGenome { fitness, genes })
+    }
+
+    // Uniformly random genes within the bounds, which may reach i64::MAX.
+    pub fn random<R: Rng>(problem: &IntegerProblem, rng: &mut R) -> IntegerGenome {
+        let genes: Vec<i64> = problem.bounds.iter()
+            .map(|&(low, high)| rng.sample(Uniform::new_inclusive(low, high)))
+            .collect();
+        let fitness = (problem.objective)(&genes);
+        IntegerGenome { fitness, genes }
+    }
+
+    pub fn genes(&self) -> &[i64] {
+        &self.genes
+    }
+}
+
+pub fn random_population<R: Rng>(problem: &IntegerProblem, count: usize, rng: &mut R) -> Vec<IntegerGenome> {
+    (0..count).map(|_| IntegerGenome::random(problem, rng)).collect()
+}
+
+// Flips one random bit of the Gray code of `value - low`, clamping a result
+// past `high` to it.
+pub(crate) fn gray_flip<R: Rng>(value: i64, low: i64, high: i64, rng: &mut R) -> i64 {
+    let range = high.wrapping_sub(low) as u64;
+    if range == 0 {
+        return value;
+    }
+
+    let bits = 64 - range.leading_zeros();
+    let offset = value.wrapping_sub(low) as u64;
+    let flipped = from_gray(to_gray(offset) ^ (1 << rng.gen_range(0, bits)));
+    low.wrapping_add(flipped.min(range) as i64)
+}
+
+fn to_gray(value: u64) -> u64 {
+    value ^ (value >> 1)
+}
+
+fn from_gray(mut gray: u64) -> u64 {
+    let mut value = gray;
+    while gray > 0 {
+        gray >>= 1;
+        value ^= gray;
+    }
+    value
+}
+
+impl Chromosome for IntegerGenome {
+    type Problem = IntegerProblem;
+
+    fn fitness(&self) -> f64 {
+        self.fitness
+    }
+
+    // Uniform crossover: each gene comes from either parent with equal odds.
+    fn crossover<R: Rng>(&self, other: &IntegerGenome, problem: &IntegerProblem, rng: &mut R) -> IntegerGenome {
+        let genes: Vec<i64> = self.genes.iter().zip(&other.genes)
+            .map(|(&a, &b)| if rng.gen() { a } else { b })
+            .collect();
+        let fitness = (problem.objective)(&genes);
+
+        IntegerGenome { fitness, genes }
+    }
+
+    // Flips one random bit of one random gene's Gray-coded offset from its
+    // lower bound; see gray_flip.
+    fn mutate<R: Rng>(&mut self, problem: &IntegerProblem, rng: &mut R) {
+        let i = rng.gen_range(0, self.genes.len());
+        let (low, high) = problem.bounds[i];
+        self.genes[i] = gray_flip(self.genes[i], low, high, rng);
+        self.fitness = (problem.objective)(&self.genes);
+    }
+
+    fn evaluate(&mut self, problem: &IntegerProblem) {
+        self.fitness = (problem.objective)(&self.genes);
+    }
+
+    fn tie_break(&self, other: &IntegerGenome) -> Ordering {
+        self.genes.cmp(&other.genes)
+    }
+
+    fn fingerprint(&self) -> u64 {
+        let mut hash = Fnv::new();
+        for &gene in &self.genes {
+            hash.feed(gene as u64);
+        }
+        hash.finish()
+    }
+}
+
+// Manhattan distance between the gene vectors.
+impl Distance for IntegerGenome {
+    fn distance(&self, other: &IntegerGenome) -> f64 {
+        self.genes.iter().zip(&other.genes).map(|(a, b)| a.abs_diff(*b) as f64).sum()
+    }
+}
+
+impl fmt::Display for IntegerGenome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Fitness: {}, Genes: {:?}", self.fitness, self.genes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use super::*;
+
+    #[test]
+    fn random_genes_reach_bounds_at_the_edge_of_i64() {
+        let bounds = vec![(i64::MAX - 1, i64::MAX), (i64::MIN, i64::MAX), (i64::MAX, i64::MAX)];
+        let problem = IntegerProblem::new(bounds.clone(), |_| 1.0).unwrap();
+        let mut rng = SmallRng::seed_from_u64(3);
+
+        let mut seen_max = false;
+        for _ in 0..100 {
+            let genome = IntegerGenome::random(&problem, &mut rng);
+            assert!(genome.genes().iter().zip(&bounds).all(|(g, &(low, high))| low <= *g && *g <= high));
+            seen_max |= genome.genes()[0] == i64::MAX;
+        }
+        assert!(seen_max);
+    }
+
+    #[test]
+    fn distance_spans_the_whole_i64_range() {
+        let problem = IntegerProblem::new(vec![(i64::MIN, i64::MAX)], |_| 1.0).unwrap();
+        let low = IntegerGenome::new(vec![i64::MIN], &problem).unwrap();
+        let high = IntegerGenome::new(vec![i64::MAX], &problem).unwrap();
+        assert_eq!(low.distance(&high), u64::MAX as f64);
+        assert_eq!(high.distance(&low), u64::MAX as f64);
+    }
+}
@@ -0,0 +1,192 @@
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use rand::Rng;
+
+use crate::chromosome::{rank, Chromosome};
+use crate::error::Error;
+use crate::events::{Event, Subscriber, Subscribers};
+use crate::simulation::{Simulation, SimulationResult};
+
+// Several independent populations evolving side by side. Every `every`
+// generations each island sends clones of its `migrants` fittest members to
+// the next island in a ring, where they replace the weakest. Isolation keeps
+// the islands diverse; migration lets good building blocks spread.
+pub struct IslandModel<C: Chromosome, R> {
+    islands: Vec<Simulation<C, R>>,
+    every: usize,
+    migrants: usize,
+    generation: usize,
+    subscribers: Subscribers,
+}
+
+impl<C: Chromosome, R: Rng> IslandModel<C, R> {
+    pub fn new(islands: Vec<Simulation<C, R>>, every: usize, migrants: usize) -> Result<IslandModel<C, R>, Error> {
+        if islands.is_empty() {
+            return Err(Error::InvalidParameter { name: "islands", value: 0.0 });
+        }
+
+        Ok(IslandModel { islands, every, migrants, generation: 0, subscribers: Subscribers::default() })
+    }
+
+    // Receives Event::MigrationPerformed for every exchange. Each island
+    // still publishes its own events to its own subscribers.
+    pub fn subscribe<F>(&mut self, subscriber: F)
+    where
+        F: FnMut(&Event) + Send + 'static,
+    {
+        self.subscribe_shared(Arc::new(Mutex::new(subscriber)));
+    }
+
+    pub fn subscribe_shared(&mut self, subscriber: Subscriber) {
+        self.subscribers.push(subscriber);
+    }
+
+    pub fn islands(&self) -> &[Simulation<C, R>] {
+        &self.islands
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // The fittest solution found on any island.
+    pub fn fittest(&self) -> &C {
+        self.islands.iter()
+            .map(|island| island.fittest())
+            .min_by(|a, b| rank(*a, *b))
+            .unwrap_or_else(|| self.islands[0].fittest())
+    }
+
+    // Advances every island one generation, then migrates when due.
+    pub fn step(&mut self) {
+        for island in self.islands.iter_mut() {
+            island.step();
+        }
+        self.generation += 1;
+
+        if self.every > 0 && self.generation.is_multiple_of(self.every) {
+            self.migrate();
+        }
+    }
+
+    pub fn run(&mut self, generations: usize) -> SimulationResult<C> {
+        for _ in 0..generations {
+            self.step();
+        }
+        self.best_result()
+    }
+
+    fn best_result(&self) -> SimulationResult<C> {
+        let best = self.islands.iter()
+            .min_by(|a, b| rank(a.fittest(), b.fittest()))
+            .unwrap_or(&self.islands[0]);
+        best.result()
+    }
+
+    // Like run, but every island evolves on its own thread. Migrants travel
+    // round the ring through channels: at each migration generation an
+    // island sends its fittest to the next island and waits only for those
+    // arriving from the previous one, so there is no global barrier. All
+    // islands share a generation count, so the result is the same as run's;
+    // only the order of the MigrationPerformed events varies.
+    pub fn run_threaded(&mut self, generations: usize) -> SimulationResult<C>
+    where
+        Simulation<C, R>: Send,
+        C: Send,
+    {
+        let count = self.islands.len();
+        let migrating = count > 1 && self.migrants > 0 && self.every > 0;
+        let (mut senders, receivers): (Vec<_>, Vec<_>) = (0..count).map(|_| mpsc::channel::<Vec<C>>()).unzip();
+        // Island i sends into channel i + 1 and receives from channel i.
+        senders.rotate_left(1);
+
+        let (start, every, migrants) = (self.generation, self.every, self.migrants);
+        let (islands, subscribers) = (&mut self.islands, &self.subscribers);
+        thread::scope(|scope| {
+            for (to, ((island, outbox), inbox)) in islands.iter_mut().zip(senders).zip(receivers).enumerate() {
+                scope.spawn(move || {
+                    for generation in start + 1..=start + generations {
+                        island.step();
+                        if !migrating || !generation.is_multiple_of(every) {
+                            continue;
+                        }
+
+                        // A neighbour that panicked has hung up; its panic
+                        // resurfaces when the scope joins.
+                        let _ = outbox.send(island.top(migrants));
+                        if let Ok(arrivals) = inbox.recv() {
+                            island.replace_weakest(&arrivals);
+                            subscribers.publish(Event::MigrationPerformed {
+                                generation,
+                                from: (to + count - 1) % count,
+                                to,
+                                migrants: arrivals.len(),
+                            });
+                        }
+                    }
+                });
+            }
+        });
+
+        self.generation += generations;
+        self.best_result()
+    }
+
+    // All migrants are picked before any arrive, so a good individual moves
+    // one island per migration rather than racing round the ring.
+    fn migrate(&mut self) {
+        let count = self.islands.len();
+        if count < 2 || self.migrants == 0 {
+            return;
+        }
+
+        let outgoing: Vec<Vec<C>> = self.islands.iter().map(|island| island.top(self.migrants)).collect();
+        for (from, migrants) in outgoing.into_iter().enumerate() {
+            let to = (from + 1) % count;
+            self.islands[to].replace_weakest(&migrants);
+            self.subscribers.publish(Event::MigrationPerformed {
+                generation: self.generation,
+                from,
+                to,
+                migrants: migrants.len(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use super::*;
+    use crate::city::City;
+    use crate::path::{initial_population, Path};
+
+    fn model() -> IslandModel<Path, SmallRng> {
+        let cities: Vec<City> = (0..12).map(|i| City::new((i * 5 % 7) as f64, (i * 3 % 11) as f64)).collect();
+        let islands = (0..3).map(|seed| {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            let population = initial_population(&cities, 20, &mut rng);
+            Simulation::with_rng(population, cities.clone(), 50, 0.8, 0.1, 0.2, rng).unwrap()
+        }).collect();
+        IslandModel::new(islands, 5, 2).unwrap()
+    }
+
+    #[test]
+    fn threaded_islands_evolve_as_the_serial_model_does() {
+        let (mut serial, mut threaded) = (model(), model());
+        let migrations = Arc::new(Mutex::new(0));
+        let counter = Arc::clone(&migrations);
+        threaded.subscribe(move |_| *counter.lock().unwrap() += 1);
+
+        assert_eq!(serial.run(30).cost, threaded.run_threaded(30).cost);
+        assert_eq!(threaded.generation(), 30);
+        assert_eq!(*migrations.lock().unwrap(), 3 * 6);
+        for (a, b) in serial.islands().iter().zip(threaded.islands()) {
+            assert_eq!(a.fittest().order(), b.fittest().order());
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use crate::error::Error;
+
+// Just enough JSON for GeoJSON, checkpoint city names and MLflow responses:
+// every value is kept, numbers as f64.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    // The value under `key` if this is an object that has one.
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+// `text` as a JSON string literal, for the writers that emit JSON.
+pub(crate) fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+pub(crate) fn parse(text: &str) -> Result<Value, Error> {
+    let mut parser = Parser { chars: text.chars().collect(), pos: 0, line: 1 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.chars.len() {
+        return Err(parser.error("trailing characters after JSON value"));
+    }
+    Ok(value)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn error(&self, reason: &str) -> Error {
+        Error::Parse { line: self.line, reason: reason.to_string() }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        self.skip_whitespace();
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            _ => Err(self.error(&format!("expected '{}'", expected))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Value::String),
+            Some('t') => self.keyword("true", Value::Bool(true)),
+            Some('f') => self.keyword("false", Value::Bool(false)),
+            Some('n') => self.keyword("null", Value::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: Value) -> Result<Value, Error> {
+        for expected in word.chars() {
+            if self.next() != Some(expected) {
+                return Err(self.error(&format!("expected {}", word)));
+            }
+        }
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<Value, Error> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || "+-.eE".contains(c)) {
+            self.next();
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse().map(Value::Number).map_err(|_| self.error(&format!("invalid number {}", text)))
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.next() {
+                    Some('n') => s.push('\n'),
+                    Some('t') => s.push('\t'),
+                    Some('r') => s.push('\r'),
+                    Some('b') => s.push('\u{8}'),
+                    Some('f') => s.push('\u{c}'),
+                    Some('u') => s.push(self.unicode_escape()?),
+                    Some(c) if "\"\\/".contains(c) => s.push(c),
+                    _ => return Err(self.error("invalid escape in string")),
+                },
+                Some(c) => s.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    // The character after a `\u`, joining a UTF-16 surrogate pair if needed.
+    fn unicode_escape(&mut self) -> Result<char, Error> {
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if self.next() != Some('\\') || self.next() != Some('u') {
+                return Err(self.error("unpaired surrogate in string"));
+            }
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("unpaired surrogate in string"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, Error> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self.next().and_then(|c| c.to_digit(16));
+            code = code * 16 + digit.ok_or_else(|| self.error("invalid unicode escape"))?;
+        }
+        Ok(code)
+    }
+
+    fn array(&mut self) -> Result<Value, Error> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.next();
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Value::Array(items)),
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, Error> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.next();
+            return Ok(Value::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            members.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Value::Object(members)),
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_parsed() {
+        let value = parse(r#" {"a": [1, -2.5e1, true, false, null], "b": {"c": "d"}, "e": {}} "#).unwrap();
+        let a = value.get("a").and_then(Value::as_array).unwrap();
+        assert_eq!(a, &[Value::Number(1.0), Value::Number(-25.0), Value::Bool(true), Value::Bool(false), Value::Null]);
+        assert_eq!(value.get("b").and_then(|b| b.get("c")).and_then(Value::as_str), Some("d"));
+        assert_eq!(value.get("e"), Some(&Value::Object(Vec::new())));
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    fn string_escapes_are_decoded() {
+        let value = parse(r#""q\" b\\ s\/ n\n t\t \u00e9 \ud83d\ude00""#).unwrap();
+        assert_eq!(value.as_str(), Some("q\" b\\ s/ n\n t\t \u{e9} \u{1f600}"));
+    }
+
+    #[test]
+    fn errors_report_their_line() {
+        assert!(matches!(parse("{\n\"a\": 1,\n\"b\" 2}"), Err(Error::Parse { line: 3, .. })));
+        assert!(matches!(parse("[1] 2"), Err(Error::Parse { line: 1, .. })));
+    }
+
+    #[test]
+    fn malformed_input_is_an_error() {
+        for text in &["", "{", "[1,]", "{\"a\"}", "tru", "\"open", "\"\\x\"", "\"\\ud83d\"", "\"\\u12\"", "1.2.3", "-", "{1: 2}"] {
+            assert!(matches!(parse(text), Err(Error::Parse { .. })), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn quoted_strings_parse_back() {
+        for text in &["", "plain", "q\" b\\", "line\nbreak\r\t\u{1}", "\u{e9}\u{1f600}"] {
+            assert_eq!(parse(&quote(text)).unwrap().as_str(), Some(*text));
+        }
+        assert_eq!(quote("a\"\n"), "\"a\\\"\\u000a\"");
+    }
+
+    #[test]
+    fn truncated_documents_never_panic() {
+        let text = r#"{"type": "Feature", "geometry": {"coordinates": [1.5, -2e3]}, "name": "\u00e9"}"#;
+        assert!(parse(text).is_ok());
+        for end in (0..text.len()).filter(|&end| text.is_char_boundary(end)) {
+            assert!(parse(&text[..end]).is_err());
+        }
+    }
+}
@@ -0,0 +1,68 @@
+pub mod analysis;
+pub mod archive;
+mod checkpoint;
+pub mod chromosome;
+pub mod city;
+pub mod compare;
+pub mod constraints;
+pub mod cost;
+pub mod crossover;
+pub mod error;
+pub mod events;
+pub mod export;
+pub mod hall_of_fame;
+pub mod instances;
+pub mod integer;
+pub mod islands;
+mod json;
+pub mod local_search;
+pub mod matrix;
+pub mod meta;
+pub mod metric;
+#[cfg(feature = "mlflow")]
+pub mod mlflow;
+pub mod mixed;
+pub mod mutation;
+mod parallel;
+pub mod path;
+pub mod quick;
+pub mod render;
+pub mod restarts;
+pub mod route;
+pub mod selection;
+pub mod simulation;
+pub mod stats;
+#[cfg(feature = "tensorboard")]
+pub mod tensorboard;
+pub mod tsplib;
+
+pub use crate::analysis::{mst_weight, nearest_neighbour_tour, one_tree_bound, summarize, InstanceSummary};
+pub use crate::archive::Archive;
+pub use crate::chromosome::{Chromosome, Distance};
+pub use crate::city::{sample_weighted, subset, City};
+pub use crate::compare::{compare, Comparison};
+pub use crate::constraints::{feasible_integer_population, feasible_mixed_population, LinearConstraint};
+pub use crate::cost::{CostMode, CostModel, Objective, TourType};
+pub use crate::crossover::{Crossover, CrossoverOperator, CycleCrossover, OrderCrossover, PartiallyMapped, SinglePoint};
+pub use crate::error::Error;
+pub use crate::events::{Event, Subscriber, TopKSink};
+pub use crate::export::ExportFormat;
+pub use crate::integer::{IntegerGenome, IntegerProblem};
+pub use crate::islands::IslandModel;
+pub use crate::local_search::{or_opt, two_opt};
+pub use crate::matrix::DistanceMatrix;
+pub use crate::meta::{tune_parameters, MetaSettings, TunedParameters};
+pub use crate::metric::{DistanceMetric, Euclidean, Haversine, Manhattan};
+#[cfg(feature = "mlflow")]
+pub use crate::mlflow::MlflowRun;
+pub use crate::mixed::{Gene, GeneSpec, MixedGenome, MixedProblem};
+pub use crate::mutation::{Insertion, Inversion, Mutation, MutationOperator, Scramble, Swap, WeightedMix};
+pub use crate::path::{initial_population, initial_population_with_threads, Path, TspProblem};
+pub use crate::quick::{evolve, order_crossover, Best, EvolveConfig};
+pub use crate::restarts::{bootstrap_min, multi_start, ConfidenceInterval, MultiStartReport};
+pub use crate::route::{Route, Stop};
+pub use crate::selection::{Mating, Replacement, Selection};
+pub use crate::simulation::{generation_seed, Estimate, GenerationStats, Generations, PhaseReport, Simulation, SimulationResult};
+pub use crate::stats::StatsRecorder;
+#[cfg(feature = "tensorboard")]
+pub use crate::tensorboard::TensorBoardWriter;
@@ -0,0 +1,257 @@
+use crate::cost::TourType;
+use crate::matrix::DistanceMatrix;
+
+// Moves must shorten the tour by more than this to be applied, so rounding
+// noise cannot make a pass loop forever.
+const EPSILON: f64 = 1e-9;
+
+// Improvement moves assume a symmetric matrix; on an asymmetric one their
+// deltas are estimates, so the number of passes is capped.
+pub(crate) const MAX_PASSES: usize = 50;
+
+// One pass of 2-opt over `order`: every segment whose reversal shortens the
+// tour is reversed, first improvement first. Returns whether any move was
+// applied. Only matrix distances are compared, so the pass suits every cost
+// objective in which the tour's travel distance is what varies. A closed
+// tour keeps its first city in place and an open one both its ends.
+pub fn two_opt(order: &mut [usize], matrix: &DistanceMatrix, tour_type: TourType) -> bool {
+    let n = order.len();
+    if n < 3 {
+        return false;
+    }
+
+    let mut improved = false;
+    let last = if tour_type == TourType::Closed { n - 1 } else { n - 2 };
+    for s in 1..last {
+        for j in s + 1..=last {
+            // Reversing all but the first city of a closed tour yields the
+            // same tour backwards.
+            if tour_type == TourType::Closed && s == 1 && j == n - 1 {
+                continue;
+            }
+
+            let prev = neighbour(order, s as isize - 1, tour_type);
+            let next = neighbour(order, j as isize + 1, tour_type);
+            let delta = edge(matrix, prev, Some(order[j])) + edge(matrix, Some(order[s]), next)
+                - edge(matrix, prev, Some(order[s]))
+                - edge(matrix, Some(order[j]), next);
+
+            if delta < -EPSILON {
+                order[s..=j].reverse();
+                improved = true;
+            }
+        }
+    }
+    improved
+}
+
+// One pass of Or-opt: every run of one to three consecutive cities is tried
+// at every other place in the tour, forwards and reversed, and moved when
+// that shortens the tour. Returns whether any move was applied. The ends of
+// an open tour are never moved and nothing is inserted beyond them.
+pub fn or_opt(order: &mut Vec<usize>, matrix: &DistanceMatrix, tour_type: TourType) -> bool {
+    let mut improved = false;
+
+    for len in 1..=3 {
+        let n = order.len();
+        if n < len + 2 {
+            break;
+        }
+
+        let (first, end) = match tour_type {
+            TourType::Closed => (0, n),
+            TourType::Open => (1, n - 1),
+        };
+        let mut s = first;
+        while s + len <= end {
+            if let Some(moved) = best_move(order, s, len, matrix, tour_type) {
+                *order = moved;
+                improved = true;
+            }
+            s += 1;
+        }
+    }
+    improved
+}
+
+// The tour with the run of `len` cities at `s` moved to its best new place,
+// or None when no place is shorter than where it is now.
+fn best_move(
+    order: &[usize],
+    s: usize,
+    len: usize,
+    matrix: &DistanceMatrix,
+    tour_type: TourType,
+) -> Option<Vec<usize>> {
+    let segment = &order[s..s + len];
+    let (head, tail) = (segment[0], segment[len - 1]);
+    let prev = neighbour(order, s as isize - 1, tour_type);
+    let next = neighbour(order, (s + len) as isize, tour_type);
+    let removed = edge(matrix, prev, Some(head)) + edge(matrix, Some(tail), next) - edge(matrix, prev, next);
+
+    let rest: Vec<usize> = order[..s].iter().chain(&order[s + len..]).copied().collect();
+    let m = rest.len();
+    let (places, original) = match tour_type {
+        // Inserting before the first city of a closed tour is the same as after its last.
+        TourType::Closed => (1..=m, if s == 0 { m } else { s }),
+        TourType::Open => (1..=m - 1, s),
+    };
+
+    let mut best: Option<(f64, usize, bool)> = None;
+    for t in places {
+        let x = t.checked_sub(1).map(|k| rest[k]);
+        let y = match tour_type {
+            TourType::Closed => Some(rest[t % m]),
+            TourType::Open => rest.get(t).copied(),
+        };
+        let bridge = edge(matrix, x, y);
+        let forwards = edge(matrix, x, Some(head)) + edge(matrix, Some(tail), y) - bridge;
+        let reversed = edge(matrix, x, Some(tail)) + edge(matrix, Some(head), y) - bridge;
+
+        let mut candidates = vec![(reversed - removed, t, true)];
+        if t != original {
+            candidates.push((forwards - removed, t, false));
+        }
+        for candidate in candidates {
+            if candidate.0 < -EPSILON && best.is_none_or(|b| candidate.0 < b.0) {
+                best = Some(candidate);
+            }
+        }
+    }
+
+    best.map(|(_, t, reversed)| {
+        let mut moved = Vec::with_capacity(order.len());
+        moved.extend_from_slice(&rest[..t]);
+        if reversed {
+            moved.extend(segment.iter().rev());
+        } else {
+            moved.extend_from_slice(segment);
+        }
+        moved.extend_from_slice(&rest[t..]);
+        moved
+    })
+}
+
+// The city at position `k`, wrapping around a closed tour; an open tour has
+// nothing before its first city or after its last.
+fn neighbour(order: &[usize], k: isize, tour_type: TourType) -> Option<usize> {
+    let n = order.len() as isize;
+    match tour_type {
+        TourType::Closed => Some(order[k.rem_euclid(n) as usize]),
+        TourType::Open if k >= 0 && k < n => Some(order[k as usize]),
+        TourType::Open => None,
+    }
+}
+
+fn edge(matrix: &DistanceMatrix, a: Option<usize>, b: Option<usize>) -> f64 {
+    match (a, b) {
+        (Some(a), Some(b)) => matrix.distance(a, b),
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+    use rand::seq::SliceRandom;
+    use rand::Rng;
+
+    use super::*;
+
+    const TOUR_TYPES: [TourType; 2] = [TourType::Closed, TourType::Open];
+
+    fn matrix(points: &[(f64, f64)]) -> DistanceMatrix {
+        DistanceMatrix::from_fn(points.len(), |a, b| {
+            let (dx, dy) = (points[a].0 - points[b].0, points[a].1 - points[b].1);
+            (dx * dx + dy * dy).sqrt()
+        })
+    }
+
+    fn length(order: &[usize], matrix: &DistanceMatrix, tour_type: TourType) -> f64 {
+        let legs: f64 = order.windows(2).map(|w| matrix.distance(w[0], w[1])).sum();
+        match (tour_type, order.first(), order.last()) {
+            (TourType::Closed, Some(&first), Some(&last)) => legs + matrix.distance(last, first),
+            _ => legs,
+        }
+    }
+
+    fn is_permutation(order: &[usize]) -> bool {
+        let mut sorted = order.to_vec();
+        sorted.sort_unstable();
+        sorted.into_iter().eq(0..order.len())
+    }
+
+    fn square() -> DistanceMatrix {
+        matrix(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)])
+    }
+
+    #[test]
+    fn passes_never_lengthen_the_tour_and_keep_a_permutation() {
+        let mut rng = SmallRng::seed_from_u64(9);
+        for n in 0..12 {
+            let points: Vec<(f64, f64)> = (0..n).map(|_| (rng.gen_range(0.0, 100.0), rng.gen_range(0.0, 100.0))).collect();
+            let matrix = matrix(&points);
+            for &tour_type in &TOUR_TYPES {
+                let mut order: Vec<usize> = (0..n).collect();
+                order.shuffle(&mut rng);
+                let before = length(&order, &matrix, tour_type);
+
+                two_opt(&mut order, &matrix, tour_type);
+                let after_two_opt = length(&order, &matrix, tour_type);
+                assert!(after_two_opt <= before + EPSILON);
+                assert!(is_permutation(&order));
+
+                or_opt(&mut order, &matrix, tour_type);
+                assert!(length(&order, &matrix, tour_type) <= after_two_opt + EPSILON);
+                assert!(is_permutation(&order));
+            }
+        }
+    }
+
+    #[test]
+    fn open_tours_keep_their_ends() {
+        let mut rng = SmallRng::seed_from_u64(4);
+        let points: Vec<(f64, f64)> = (0..10).map(|_| (rng.gen_range(0.0, 100.0), rng.gen_range(0.0, 100.0))).collect();
+        let matrix = matrix(&points);
+        for _ in 0..20 {
+            let mut order: Vec<usize> = (0..10).collect();
+            order.shuffle(&mut rng);
+            let ends = (order[0], order[9]);
+
+            let mut reversed = order.clone();
+            two_opt(&mut reversed, &matrix, TourType::Open);
+            assert_eq!((reversed[0], reversed[9]), ends);
+
+            let mut moved = order.clone();
+            or_opt(&mut moved, &matrix, TourType::Open);
+            assert_eq!((moved[0], moved[9]), ends);
+        }
+    }
+
+    #[test]
+    fn open_tours_assume_no_closing_edge() {
+        // Optimal as a path, though closing it would cross back over the line.
+        let matrix = matrix(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0), (10.0, 0.0)]);
+        let mut order = vec![0, 1, 2, 3, 4];
+        assert!(!two_opt(&mut order, &matrix, TourType::Open));
+        assert!(!or_opt(&mut order, &matrix, TourType::Open));
+        assert_eq!(order, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn crossed_square_is_uncrossed() {
+        let matrix = square();
+        for &tour_type in &TOUR_TYPES {
+            let optimum = if tour_type == TourType::Closed { 4.0 } else { 3.0 };
+
+            let mut order = vec![0, 2, 1, 3];
+            assert!(two_opt(&mut order, &matrix, tour_type));
+            assert!((length(&order, &matrix, tour_type) - optimum).abs() < EPSILON);
+
+            let mut order = vec![0, 2, 1, 3];
+            assert!(or_opt(&mut order, &matrix, tour_type));
+            assert!((length(&order, &matrix, tour_type) - optimum).abs() < EPSILON);
+        }
+    }
+}
@@ -1,211 +1,205 @@
-use std::fmt;
+mod cli;
+mod self_test;
 
-use rand::{thread_rng, Rng};
-use rand::seq::SliceRandom;
-use rand::distributions::{Distribution, Uniform};
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::process;
 
-pub struct City {
-    x: f64,
-    y: f64,
-}
+use rand::{FromEntropy, SeedableRng};
+use rand::rngs::SmallRng;
 
-impl City {
-    pub fn new(x: f64, y: f64) -> City {
-        City { x, y }
-    }
-}
-
-#[derive(Clone)]
-pub struct Path {
-    fitness: f64,
-    order: Vec<usize>
-}
+use genetic::{
+    compare, initial_population_with_threads, City, Error, Haversine, Manhattan, render, Simulation,
+    summarize, tsplib,
+};
 
-impl Path {
-    pub fn breed(&self, other: &Path, city_list: &Vec<City>) -> Path {
-        let order = Path::crossover_order(&self.order, &other.order);
-        let fitness = Path::calculate_fitness(&order, city_list);
+use crate::cli::{BenchOptions, Command, Metric, SolveOptions, Trial};
 
-        Path { fitness, order }
-    }
-
-    fn crossover_order(mother: &Vec<usize>, father: &Vec<usize>) -> Vec<usize> {
-        let mut rng = thread_rng();
-        let crossover_point = Uniform::new(0, mother.len()).sample(&mut rng);
+fn example_cities() -> Vec<City> {
+    vec![
+        City::new(1.0, 3.0),
+        City::new(1.0, 2.0),
+        City::new(1.0, 1.0),
+        City::new(4.0, 3.0),
+        City::new(2.0, 1.0),
+        City::new(3.0, 3.0),
+        City::new(3.0, 2.0),
+        City::new(3.0, 1.0),
+        City::new(4.0, 4.0),
+    ]
+}
 
-        let mother_dna = &mother[0..crossover_point];
-        let mut father_dna: Vec<usize> = father.iter().filter_map(|d| {
-            if !mother_dna.contains(d) {
-                return Some(*d)
+// The simulation `options` describe, before any output is attached.
+fn build(options: &SolveOptions) -> Result<Simulation, Error> {
+    let sim = match &options.resume {
+        Some(file) => Simulation::resume(file)?,
+        None => {
+            let cities = match &options.input {
+                Some(file) => cli::read_cities(file)?,
+                None => example_cities(),
+            };
+
+            let mut rng = match options.seed {
+                Some(seed) => SmallRng::seed_from_u64(seed),
+                None => SmallRng::from_entropy(),
+            };
+            let init = initial_population_with_threads(&cities, options.population, options.threads, &mut rng);
+
+            let sim = Simulation::with_rng(
+                init,
+                cities,
+                options.generations,
+                options.crossover_rate,
+                options.mutation_rate,
+                options.survival_rate,
+                rng,
+            )?
+            .with_elitism(options.elitism)?
+            .with_tour_type(options.tour_type);
+
+            // Seeded runs log each generation's seed so it can be replayed alone.
+            match options.seed {
+                Some(seed) => sim.with_generation_seeds(seed),
+                None => sim,
             }
-            None
-        }).collect();
+        }
+    };
+    let sim = match (&options.metric, &options.input) {
+        (Metric::Euclidean, _) => sim,
+        (Metric::Manhattan, _) => sim.with_metric(&Manhattan),
+        (Metric::Haversine, _) => sim.with_metric(&Haversine::EARTH_KM),
+        (Metric::Tsplib, Some(file)) => sim.with_metric(&tsplib::load_tsp(file)?.edge_weight_type),
+        (Metric::Tsplib, None) => sim,
+    };
+    let mut sim = sim.with_threads(options.threads)
+        .with_mst_bound()
+        .with_local_search(options.local_search_rate)?
+        .with_selection(options.selection)?
+        .with_oversampling(options.oversampling)?
+        .with_mating(options.mating)?
+        .with_replacement(options.replacement)?
+        .with_crossover(options.crossover)
+        .with_mutation(options.mutation);
+
+    if let Some((min, max, target)) = options.adaptive_mutation {
+        sim = sim.with_adaptive_mutation(min, max, target)?;
+    }
+    Ok(sim)
+}
 
-        let mut child = Vec::new();
-        child.extend_from_slice(mother_dna);
-        child.append(&mut father_dna);
+fn solve(options: SolveOptions) -> Result<(), Error> {
+    let mut sim = build(&options)?;
 
-        child
+    if options.stats.is_some() || options.export.is_some() {
+        sim = sim.with_stats_recorder();
     }
 
-    pub fn mutate(&mut self, city_list: &Vec<City>) {
-        let mut rng = thread_rng();
-        let point_one = Uniform::new(0, self.order.len()).sample(&mut rng);
-        let point_two = Uniform::new(0, self.order.len()).sample(&mut rng);
-
-        self.order.swap(point_one, point_two);
-        self.fitness = Path::calculate_fitness(&self.order, &city_list);
+    if options.auto_mutation {
+        let rate = sim.calibrate_mutation_rate(&[0.001, 0.01, 0.05, 0.1, 0.2], 500)?;
+        println!("calibrated mutation rate: {}", rate);
     }
 
-    pub fn calculate_fitness(path: &Vec<usize>, city_list: &Vec<City>) -> f64 {
-        let path_length = city_list.len();
-        let mut cost = 0.0;
-        for i in 0..path_length - 1 {
-            let a = &city_list[path[i]];
-            let b = &city_list[path[i + 1]];
-            cost = cost + ((a.x - b.x).powf(2.0) + (a.y - b.y).powf(2.0)).sqrt();
+    println!("starting iterations");
+    let progress = options.progress;
+    let stats = sim.run_until(|stats| {
+        if progress > 0 && stats.generation.is_multiple_of(progress) {
+            println!("{}", stats);
         }
-
-        1.0 / cost
+        false
+    });
+    println!("{}", sim.fittest());
+    if sim.problem().city_list.iter().any(|city| city.name().is_some()) {
+        println!("route: {}", sim.route());
     }
-}
-
-impl fmt::Display for Path {
-    fn fmt(& self, f: &mut fmt::Formatter) -> fmt::Result {
-        let string_order: Vec<String> = self.order.iter().map(|i| i.to_string()).collect();
-        write!(f, "Fitness: {}, Path: {}", self.fitness, string_order.join("->"))
+    println!("cost: {}", stats.best_cost);
+    if let Some(gap) = stats.gap {
+        println!("gap to lower bound: {:.2}%", gap * 100.0);
     }
-}
+    println!("improvement by phase:\n{}", sim.phase_report());
 
-pub struct Simulation {
-    population: Vec<Path>,
-    city_list: Vec<City>,
-    max_iterations: usize,
-    crossover_rate: f64,
-    mutation_rate: f64,
-    survival_rate: f64,
-}
-
-impl Simulation {
-    pub fn new(
-        initial_population: Vec<Path>,
-        city_list: Vec<City>,
-        max_iterations: usize,
-        crossover_rate: f64,
-        mutation_rate: f64,
-        survival_rate: f64,
-    ) -> Simulation {
-        Simulation {
-            population: initial_population,
-            city_list,
-            max_iterations,
-            crossover_rate,
-            mutation_rate,
-            survival_rate,
-        }
+    if let (Some(file), Some(recorder)) = (&options.stats, sim.stats_recorder()) {
+        recorder.save_csv(file)?;
     }
-
-    pub fn run(&mut self) -> () {
-        let mut fittest = self.find_fittest();
-        println!("starting iterations");
-
-        for _ in 0..self.max_iterations {
-            self.generate_next_generation();
-
-            let challenger = self.find_fittest();
-
-            if challenger.fitness > fittest.fitness {
-                fittest = challenger;
-            }
-        }
-        println!("{}", fittest);
+    if let Some(file) = &options.svg {
+        fs::write(file, render::path_svg(sim.fittest(), sim.problem()))?;
     }
-
-    fn find_fittest(&self) -> Path {
-        let mut fittest = &self.population[0];
-
-        for i in 1..self.population.len() {
-            let p = &self.population[i];
-            if p.fitness > fittest.fitness {
-                fittest = p;
-            }
-        }
-
-        return fittest.clone();
+    if let Some((file, format)) = &options.export {
+        let mut writer = BufWriter::new(File::create(file)?);
+        sim.export_results(&mut writer, *format)?;
+        writer.flush()?;
+    }
+    if let Some(file) = &options.checkpoint {
+        sim.save_checkpoint(file)?;
     }
 
-    fn generate_next_generation(&mut self) {
-        self.population.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap());
-
-        let breeding_count = (self.population.len() as f64 * self.crossover_rate) as usize;
-        let surviving_parent_count = (breeding_count as f64 * self.survival_rate) as usize;
-
-        let mut breeding_population = Vec::new();
-        breeding_population.extend_from_slice(&self.population[0..breeding_count]);
-
-        let mut offspring = Vec::new();
-
-        let mut rng = thread_rng();
-        let pcnt_range = Uniform::new(0, breeding_population.len());
-        for i in 0..self.population.len() - surviving_parent_count - 2 {
-            let rs = pcnt_range.sample(&mut rng);
-            offspring.push(
-                breeding_population[i % breeding_population.len()].breed(
-                    &breeding_population[rs],
-                    &self.city_list
-                )
-            );
-        }
-
-        let mut next_generation = Vec::new();
-        next_generation.extend_from_slice(&self.population[0..surviving_parent_count]);
-        next_generation.append(&mut offspring);
-        // Add a few weak individuals to keep the genetic diversity higher
-        next_generation.extend_from_slice(&self.population[self.population.len() - 2..self.population.len()]);
-
-        assert!(next_generation.len() == self.population.len());
+    Ok(())
+}
 
-        for p in 0..next_generation.len() {
-            if thread_rng().gen_bool(self.mutation_rate) {
-                next_generation[p].mutate(&self.city_list);
+fn bench(options: BenchOptions) -> Result<(), Error> {
+    let mut trials = Vec::new();
+    for &selection in &options.selections {
+        for &crossover in &options.crossovers {
+            for &mutation in &options.mutations {
+                trials.push(Trial { selection, crossover, mutation });
             }
         }
-
-        self.population = next_generation;
     }
-}
-
-fn initial_population(city_list: &Vec<City>, population_count: usize) -> Vec<Path> {
-    let base_list: Vec<usize> = (0..city_list.len()).collect();
-    let mut population = Vec::new();
 
-    for _ in 0..population_count {
-        let mut p = base_list.clone();
-        let mut rng = thread_rng();
-        p.shuffle(&mut rng);
-        let fitness = Path::calculate_fitness(&p, city_list);
-
-        population.push(Path { fitness, order: p });
+    let comparisons = compare(&trials, options.runs, |trial, run| {
+        let mut solve = options.solve.clone();
+        solve.selection = trial.selection;
+        solve.crossover = trial.crossover;
+        solve.mutation = trial.mutation;
+        solve.seed = options.solve.seed.map(|seed| seed.wrapping_add(run as u64));
+        build(&solve)
+    })?;
+
+    let width = comparisons.iter().map(|c| c.label.len()).max().unwrap_or(0).max("configuration".len());
+    println!("{:<width$}  {:>12}  {:>12}  {:>10}  {:>10}", "configuration", "mean cost", "best cost", "std dev", "runtime", width = width);
+    for c in &comparisons {
+        println!(
+            "{:<width$}  {:>12.2}  {:>12.2}  {:>10.2}  {:>9.3}s",
+            c.label,
+            c.mean_cost,
+            c.best_cost,
+            c.std_dev,
+            c.mean_runtime.as_secs_f64(),
+            width = width
+        );
     }
-
-    population
+    Ok(())
 }
 
 fn main() {
-    let cities = vec![
-        City::new(1.0, 3.0),
-        City::new(1.0, 2.0),
-        City::new(1.0, 1.0),
-        City::new(4.0, 3.0),
-        City::new(2.0, 1.0),
-        City::new(3.0, 3.0),
-        City::new(3.0, 2.0),
-        City::new(3.0, 1.0),
-        City::new(4.0, 4.0),
-    ];
-
-    let init = initial_population(&cities, 100);
+    let command = match cli::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, cli::USAGE);
+            process::exit(2);
+        }
+    };
 
-    let mut sim = Simulation::new(init, cities, 100, 0.8, 0.001, 0.2);
-    sim.run();
+    let outcome = match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+        Command::Solve(options) => solve(*options),
+        Command::Bench(options) => bench(*options),
+        Command::SelfTest => {
+            if !self_test::run() {
+                process::exit(1);
+            }
+            Ok(())
+        }
+        Command::Inspect(file) => cli::read_cities(&file)
+            .and_then(|cities| summarize(&cities))
+            .map(|summary| println!("{}", summary)),
+    };
+
+    if let Err(err) = outcome {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
 }
@@ -0,0 +1,61 @@
+use crate::analysis::{one_tree, spanning_tree};
+use crate::city::City;
+use crate::metric::{DistanceMetric, Euclidean};
+
+// Pairwise edge lengths, computed once per instance so evaluating a tour is
+// a table lookup per edge instead of a square root. Stored row-major.
+#[derive(Clone, Debug)]
+pub struct DistanceMatrix {
+    size: usize,
+    distances: Vec<f64>,
+}
+
+impl DistanceMatrix {
+    // Euclidean distances between `cities`.
+    pub fn new(cities: &[City]) -> DistanceMatrix {
+        DistanceMatrix::with_metric(cities, &Euclidean)
+    }
+
+    pub fn with_metric(cities: &[City], metric: &dyn DistanceMetric) -> DistanceMatrix {
+        DistanceMatrix::from_fn(cities.len(), |a, b| metric.distance(&cities[a], &cities[b]))
+    }
+
+    // A matrix of arbitrary, possibly asymmetric costs: `distance(a, b)` is
+    // the cost of travelling from city a to city b.
+    pub fn from_fn<F>(size: usize, mut distance: F) -> DistanceMatrix
+    where
+        F: FnMut(usize, usize) -> f64,
+    {
+        let mut distances = Vec::with_capacity(size * size);
+        for a in 0..size {
+            for b in 0..size {
+                distances.push(distance(a, b));
+            }
+        }
+
+        DistanceMatrix { size, distances }
+    }
+
+    pub fn distance(&self, a: usize, b: usize) -> f64 {
+        self.distances[a * self.size + b]
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    // Weight of a minimum spanning tree under these distances; see
+    // analysis::mst_weight. Only a bound when the matrix is symmetric.
+    pub fn mst_weight(&self) -> f64 {
+        spanning_tree(self.size, |a, b| self.distance(a, b), None)
+    }
+
+    // The 1-tree bound under these distances; see analysis::one_tree_bound.
+    pub fn one_tree_bound(&self) -> f64 {
+        one_tree(self.size, |a, b| self.distance(a, b))
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+use std::sync::Arc;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::SmallRng;
+
+use crate::city::City;
+use crate::crossover::Crossover;
+use crate::error::Error;
+use crate::mixed::{random_population, Gene, GeneSpec, MixedGenome, MixedProblem};
+use crate::mutation::Mutation;
+use crate::path::{initial_population, Path};
+use crate::simulation::Simulation;
+
+// Budget and search space for tune_parameters. Every candidate parameter set
+// costs `inner_runs` runs of `inner_generations` generations, so the total
+// work is roughly outer_population * outer_generations * inner_runs inner
+// runs.
+#[derive(Clone, Debug)]
+pub struct MetaSettings {
+    pub outer_population: usize,
+    pub outer_generations: usize,
+    pub inner_generations: usize,
+    pub inner_runs: usize,
+    pub population_range: (i64, i64),
+    pub seed: u64,
+}
+
+impl Default for MetaSettings {
+    fn default() -> MetaSettings {
+        MetaSettings {
+            outer_population: 20,
+            outer_generations: 15,
+            inner_generations: 100,
+            inner_runs: 2,
+            population_range: (20, 200),
+            seed: 0,
+        }
+    }
+}
+
+// A TSP parameter set found by tune_parameters, with the mean best cost its
+// inner runs reached.
+#[derive(Clone, Debug)]
+pub struct TunedParameters {
+    pub population: usize,
+    pub crossover_rate: f64,
+    pub mutation_rate: f64,
+    pub survival_rate: f64,
+    pub crossover: Crossover,
+    pub mutation: Mutation,
+    pub mean_cost: f64,
+}
+
+impl TunedParameters {
+    // A simulation over `cities` configured with these parameters.
+    pub fn simulation<R: Rng>(
+        &self,
+        cities: Vec<City>,
+        max_iterations: usize,
+        mut rng: R,
+    ) -> Result<Simulation<Path, R>, Error> {
+        let init = initial_population(&cities, self.population, &mut rng);
+        Ok(Simulation::with_rng(
+            init,
+            cities,
+            max_iterations,
+            self.crossover_rate,
+            self.mutation_rate,
+            self.survival_rate,
+            rng,
+        )?.with_crossover(self.crossover).with_mutation(self.mutation))
+    }
+
+    fn from_genes(genes: &[Gene]) -> Option<TunedParameters> {
+        match *genes {
+            [Gene::Integer(population), Gene::Real(crossover_rate), Gene::Real(mutation_rate), Gene::Real(survival_rate), Gene::Categorical(crossover), Gene::Categorical(mutation)] => {
+                Some(TunedParameters {
+                    population: population as usize,
+                    crossover_rate,
+                    mutation_rate,
+                    survival_rate,
+                    crossover: *Crossover::ALL.get(crossover)?,
+                    mutation: *Mutation::ALL.get(mutation)?,
+                    mean_cost: f64::INFINITY,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TunedParameters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "population {}, crossover rate {:.3}, mutation rate {:.3}, survival rate {:.3}, crossover {}, mutation {} (mean cost {})",
+            self.population,
+            self.crossover_rate,
+            self.mutation_rate,
+            self.survival_rate,
+            self.crossover,
+            self.mutation,
+            self.mean_cost
+        )
+    }
+}
+
+// Mean best cost of short seeded runs with `params`. Every candidate uses
+// the same seeds, so candidates are compared on the same random streams.
+fn mean_cost(params: &TunedParameters, cities: &[City], settings: &MetaSettings) -> f64 {
+    let runs = settings.inner_runs.max(1);
+    let mut total = 0.0;
+    for run in 0..runs {
+        let rng = SmallRng::seed_from_u64(settings.seed.wrapping_add(run as u64));
+        match params.simulation(cities.to_vec(), settings.inner_generations, rng) {
+            Ok(mut sim) => total += sim.run().cost,
+            Err(_) => return f64::INFINITY,
+        }
+    }
+    total / runs as f64
+}
+
+// Meta-GA: an outer GA over MixedGenome parameter sets (population size,
+// rates and operators), each scored by short inner runs on `cities`. Returns
+// the best parameter set found.
+pub fn tune_parameters(cities: &[City], settings: &MetaSettings) -> Result<TunedParameters, Error> {
+    if cities.is_empty() {
+        return Err(Error::EmptyCityList);
+    }
+
+    let (low, high) = settings.population_range;
+    let specs = vec![
+        GeneSpec::Integer { low: low.max(2), high: high.max(low.max(2)) },
+        GeneSpec::Real { low: 0.1, high: 1.0, sigma: 0.1 },
+        GeneSpec::Real { low: 0.0, high: 0.5, sigma: 0.05 },
+        GeneSpec::Real { low: 0.0, high: 0.9, sigma: 0.1 },
+        GeneSpec::categorical(&Crossover::ALL.map(Crossover::name)),
+        GeneSpec::categorical(&Mutation::ALL.map(Mutation::name)),
+    ];
+
+    let inner_cities: Arc<Vec<City>> = Arc::new(cities.to_vec());
+    let inner_settings = settings.clone();
+    let problem = MixedProblem::new(specs, move |genes| {
+        TunedParameters::from_genes(genes)
+            .map_or(0.0, |params| 1.0 / mean_cost(&params, &inner_cities, &inner_settings))
+    })?;
+
+    let mut rng = SmallRng::seed_from_u64(settings.seed);
+    let init = random_population(&problem, settings.outer_population, &mut rng);
+    let mut outer = Simulation::with_problem(init, problem, settings.outer_generations, 0.8, 0.3, 0.2, rng)?
+        .with_elitism(1)?;
+    let result = outer.run();
+    let best: &MixedGenome = &result.best;
+
+    let mut tuned = TunedParameters::from_genes(best.genes())
+        .ok_or(Error::InvalidParameter { name: "genes", value: best.genes().len() as f64 })?;
+    tuned.mean_cost = result.cost;
+    Ok(tuned)
+}
@@ -0,0 +1,56 @@
+use crate::city::City;
+
+// How far apart two cities are. Implementations are expected to be
+// symmetric; DistanceMatrix::from_fn covers asymmetric costs.
+pub trait DistanceMetric: Send + Sync {
+    fn distance(&self, a: &City, b: &City) -> f64;
+}
+
+// Straight-line distance in the plane, the default.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Euclidean;
+
+impl DistanceMetric for Euclidean {
+    fn distance(&self, a: &City, b: &City) -> f64 {
+        a.distance_to(b)
+    }
+}
+
+// Distance along axis-aligned streets, as on a city grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Manhattan;
+
+impl DistanceMetric for Manhattan {
+    fn distance(&self, a: &City, b: &City) -> f64 {
+        (a.x - b.x).abs() + (a.y - b.y).abs()
+    }
+}
+
+// Great-circle distance on a sphere of `radius`, for cities given as
+// x = latitude and y = longitude in decimal degrees (the convention the
+// TSPLIB GEO loader uses). The result is in the unit of the radius.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Haversine {
+    pub radius: f64,
+}
+
+impl Haversine {
+    // The Earth's mean radius, giving distances in kilometres.
+    pub const EARTH_KM: Haversine = Haversine { radius: 6371.0 };
+}
+
+impl Default for Haversine {
+    fn default() -> Haversine {
+        Haversine::EARTH_KM
+    }
+}
+
+impl DistanceMetric for Haversine {
+    fn distance(&self, a: &City, b: &City) -> f64 {
+        let (lat_a, lat_b) = (a.x.to_radians(), b.x.to_radians());
+        let half_lat = (lat_b - lat_a) / 2.0;
+        let half_lon = (b.y - a.y).to_radians() / 2.0;
+        let h = half_lat.sin().powi(2) + lat_a.cos() * lat_b.cos() * half_lon.sin().powi(2);
+        2.0 * self.radius * h.sqrt().min(1.0).asin()
+    }
+}
@@ -0,0 +1,259 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
+
+use rand::Rng;
+use rand::distributions::{Distribution, Normal, Uniform};
+
+use crate::chromosome::{Chromosome, Fnv};
+use crate::error::Error;
+use crate::integer::gray_flip;
+
+// The domain of one gene and how it varies.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeneSpec {
+    // A real in [low, high]. Mutation adds Gaussian noise with standard
+    // deviation `sigma`; crossover blends the parents.
+    Real { low: f64, high: f64, sigma: f64 },
+    // An integer in [low, high], mutated by a Gray-code bit flip.
+    Integer { low: i64, high: i64 },
+    // One of the unordered labels in `domain`, stored as an index into it.
+    Categorical { domain: Vec<String> },
+}
+
+impl GeneSpec {
+    pub fn categorical(labels: &[&str]) -> GeneSpec {
+        GeneSpec::Categorical { domain: labels.iter().map(|l| l.to_string()).collect() }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gene {
+    Real(f64),
+    Integer(i64),
+    Categorical(usize),
+}
+
+type Objective = Arc<dyn Fn(&[Gene]) -> f64 + Send + Sync>;
+
+// A problem over a fixed layout of mixed genes, such as a hyperparameter
+// search, with the fitness to maximise.
+#[derive(Clone)]
+pub struct MixedProblem {
+    specs: Vec<GeneSpec>,
+    objective: Objective,
+}
+
+impl MixedProblem {
+    pub fn new<F>(specs: Vec<GeneSpec>, objective: F) -> Result<MixedProblem, Error>
+    where
+        F: Fn(&[Gene]) -> f64 + Send + Sync + 'static,
+    {
+        if specs.is_empty() {
+            return Err(Error::InvalidParameter { name: "genes", value: 0.0 });
+        }
+        for spec in &specs {
+            match spec {
+                &GeneSpec::Real { low, high, sigma } => {
+                    if low.is_nan() || high.is_nan() || low > high {
+                        return Err(Error::InvalidParameter { name: "lower bound", value: low });
+                    }
+                    if sigma.is_nan() || sigma < 0.0 {
+                        return Err(Error::InvalidParameter { name: "sigma", value: sigma });
+                    }
+                }
+                &GeneSpec::Integer { low, high } if low > high => {
+                    return Err(Error::InvalidParameter { name: "lower bound", value: low as f64 });
+                }
+                GeneSpec::Categorical { domain } if domain.is_empty() => {
+                    return Err(Error::InvalidParameter { name: "categories", value: 0.0 });
+                }
+                _ => {}
+            }
+        }
+
+        Ok(MixedProblem { specs, objective: Arc::new(objective) })
+    }
+
+    pub fn specs(&self) -> &[GeneSpec] {
+        &self.specs
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MixedGenome {
+    fitness: f64,
+    genes: Vec<Gene>,
+}
+
+impl MixedGenome {
+    pub fn new(genes: Vec<Gene>, problem: &MixedProblem) -> Result<MixedGenome, Error> {
+        if genes.len() != problem.specs.len() {
+            return Err(Error::LengthMismatch { expected: problem.specs.len(), found: genes.len() });
+        }
+        for (gene, spec) in genes.iter().zip(&problem.specs) {
+            if !in_domain(*gene, spec) {
+                return Err(Error::InvalidParameter { name: "gene", value: gene_value(*gene) });
+            }
+        }
+
+        let fitness = (problem.objective)(&genes);
+        Ok(MixedGenome { fitness, genes })
+    }
+
+    // Uniformly random genes within their domains.
+    pub fn random<R: Rng>(problem: &MixedProblem, rng: &mut R) -> MixedGenome {
+        let genes: Vec<Gene> = problem.specs.iter().map(|spec| match spec {
+            &GeneSpec::Real { low, high, .. } if low < high => Gene::Real(rng.gen_range(low, high)),
+            &GeneSpec::Real { low, .. } => Gene::Real(low),
+            &GeneSpec::Integer { low, high } => Gene::Integer(rng.sample(Uniform::new_inclusive(low, high))),
+            GeneSpec::Categorical { domain } => Gene::Categorical(rng.gen_range(0, domain.len())),
+        }).collect();
+        let fitness = (problem.objective)(&genes);
+
+        MixedGenome { fitness, genes }
+    }
+
+    pub fn genes(&self) -> &[Gene] {
+        &self.genes
+    }
+
+    // The label of categorical gene `i`, or None if gene `i` is not
+    // categorical.
+    pub fn category<'a>(&self, i: usize, problem: &'a MixedProblem) -> Option<&'a str> {
+        match (self.genes.get(i), problem.specs.get(i)) {
+            (Some(&Gene::Categorical(v)), Some(GeneSpec::Categorical { domain })) => domain.get(v).map(|l| l.as_str()),
+            _ => None,
+        }
+    }
+}
+
+fn in_domain(gene: Gene, spec: &GeneSpec) -> bool {
+    match (gene, spec) {
+        (Gene::Real(v), &GeneSpec::Real { low, high, .. }) => v >= low && v <= high,
+        (Gene::Integer(v), &GeneSpec::Integer { low, high }) => v >= low && v <= high,
+        (Gene::Categorical(v), GeneSpec::Categorical { domain }) => v < domain.len(),
+        _ => false,
+    }
+}
+
+pub fn random_population<R: Rng>(problem: &MixedProblem, count: usize, rng: &mut R) -> Vec<MixedGenome> {
+    (0..count).map(|_| MixedGenome::random(problem, rng)).collect()
+}
+
+fn gene_value(gene: Gene) -> f64 {
+    match gene {
+        Gene::Real(v) => v,
+        Gene::Integer(v) => v as f64,
+        Gene::Categorical(v) => v as f64,
+    }
+}
+
+// Uniform-category mutation: any category of the domain other than
+// `current`, with equal odds. A single-category gene keeps its only value.
+fn uniform_category<R: Rng>(current: usize, count: usize, rng: &mut R) -> Gene {
+    if count < 2 {
+        return Gene::Categorical(0);
+    }
+    if current >= count {
+        return Gene::Categorical(rng.gen_range(0, count));
+    }
+
+    let pick = rng.gen_range(0, count - 1);
+    Gene::Categorical(if pick >= current { pick + 1 } else { pick })
+}
+
+impl Chromosome for MixedGenome {
+    type Problem = MixedProblem;
+
+    fn fitness(&self) -> f64 {
+        self.fitness
+    }
+
+    // Per gene: reals are blended at a random point between the parents;
+    // integers and categories come from either parent with equal odds. A
+    // category outside the gene's domain is never inherited: the other
+    // parent's is used, or a fresh draw when neither is valid.
+    fn crossover<R: Rng>(&self, other: &MixedGenome, problem: &MixedProblem, rng: &mut R) -> MixedGenome {
+        let genes: Vec<Gene> = self.genes.iter().zip(&other.genes).zip(&problem.specs)
+            .map(|((&a, &b), spec)| match (a, b, spec) {
+                (Gene::Real(x), Gene::Real(y), _) => Gene::Real(x + rng.gen::<f64>() * (y - x)),
+                (_, _, GeneSpec::Categorical { domain }) => {
+                    let (first, second) = if rng.gen() { (a, b) } else { (b, a) };
+                    [first, second].iter().copied()
+                        .find(|&gene| in_domain(gene, spec))
+                        .unwrap_or_else(|| Gene::Categorical(rng.gen_range(0, domain.len())))
+                }
+                _ if rng.gen() => a,
+                _ => b,
+            })
+            .collect();
+        let fitness = (problem.objective)(&genes);
+
+        MixedGenome { fitness, genes }
+    }
+
+    // Mutates one random gene with its spec's operator, staying in bounds.
+    fn mutate<R: Rng>(&mut self, problem: &MixedProblem, rng: &mut R) {
+        let i = rng.gen_range(0, self.genes.len());
+        self.genes[i] = match (self.genes[i], &problem.specs[i]) {
+            (Gene::Real(v), &GeneSpec::Real { low, high, sigma }) => {
+                let noise = Normal::new(0.0, sigma).sample(rng);
+                Gene::Real((v + noise).max(low).min(high))
+            }
+            (Gene::Integer(v), &GeneSpec::Integer { low, high }) => Gene::Integer(gray_flip(v, low, high, rng)),
+            (Gene::Categorical(v), GeneSpec::Categorical { domain }) => uniform_category(v, domain.len(), rng),
+            (gene, _) => gene,
+        };
+        self.fitness = (problem.objective)(&self.genes);
+    }
+
+    fn evaluate(&mut self, problem: &MixedProblem) {
+        self.fitness = (problem.objective)(&self.genes);
+    }
+
+    fn tie_break(&self, other: &MixedGenome) -> Ordering {
+        self.genes.iter().zip(&other.genes)
+            .map(|(a, b)| gene_value(*a).partial_cmp(&gene_value(*b)).unwrap_or(Ordering::Equal))
+            .find(|o| *o != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    }
+
+    fn fingerprint(&self) -> u64 {
+        let mut hash = Fnv::new();
+        for &gene in &self.genes {
+            hash.feed(gene_value(gene).to_bits());
+        }
+        hash.finish()
+    }
+}
+
+impl fmt::Display for MixedGenome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let genes: Vec<String> = self.genes.iter().map(|gene| match gene {
+            Gene::Real(v) => v.to_string(),
+            Gene::Integer(v) => v.to_string(),
+            Gene::Categorical(v) => format!("#{}", v),
+        }).collect();
+        write!(f, "Fitness: {}, Genes: [{}]", self.fitness, genes.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use super::*;
+
+    #[test]
+    fn random_integer_genes_may_reach_i64_max() {
+        let specs = vec![GeneSpec::Integer { low: i64::MAX - 1, high: i64::MAX }];
+        let problem = MixedProblem::new(specs, |_| 1.0).unwrap();
+        let mut rng = SmallRng::seed_from_u64(3);
+
+        let genes: Vec<Gene> = (0..100).map(|_| MixedGenome::random(&problem, &mut rng).genes()[0]).collect();
+        assert!(genes.iter().all(|g| matches!(g, Gene::Integer(v) if *v >= i64::MAX - 1)));
+        assert!(genes.contains(&Gene::Integer(i64::MAX)));
+    }
+}
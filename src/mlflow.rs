@@ -0,0 +1,141 @@
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::Error;
+use crate::events::{Event, Subscriber};
+use crate::json::{self, quote, Value};
+use crate::simulation::GenerationStats;
+use crate::stats::metrics;
+
+// A run on an MLflow tracking server, logged to through its REST API. Each
+// GenerationStats field becomes a metric with the generation as its step.
+// Only plain `http://` tracking URIs are supported.
+pub struct MlflowRun {
+    host: String,
+    port: u16,
+    prefix: String,
+    run_id: String,
+}
+
+impl MlflowRun {
+    // Starts a run named `run_name` in `experiment_id` on the server at
+    // `tracking_uri`, e.g. `http://localhost:5000`.
+    pub fn start(tracking_uri: &str, experiment_id: &str, run_name: &str) -> Result<MlflowRun, Error> {
+        let address = tracking_uri.strip_prefix("http://")
+            .ok_or_else(|| failure(format!("unsupported tracking URI {}", tracking_uri)))?;
+        let (authority, prefix) = match address.find('/') {
+            Some(i) => (&address[..i], address[i..].trim_end_matches('/')),
+            None => (address, ""),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port.parse().map_err(|_| failure(format!("invalid port {}", port)))?;
+                (host, port)
+            }
+            None => (authority, 80),
+        };
+
+        let mut run = MlflowRun {
+            host: host.to_string(),
+            port,
+            prefix: prefix.to_string(),
+            run_id: String::new(),
+        };
+        let response = run.post("runs/create", &format!(
+            "{{\"experiment_id\":{},\"run_name\":{},\"start_time\":{}}}",
+            quote(experiment_id),
+            quote(run_name),
+            now_millis()
+        ))?;
+        let response = json::parse(&response)?;
+        run.run_id = ["run", "info", "run_id"].iter()
+            .try_fold(&response, |value, key| value.get(key))
+            .and_then(Value::as_str)
+            .ok_or_else(|| failure("no run_id in the server's response".to_string()))?
+            .to_string();
+        Ok(run)
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    // Logs every metric of `stats` in one batch. Non-finite values, which
+    // MLflow cannot store, are skipped.
+    pub fn log(&self, stats: &GenerationStats) -> Result<(), Error> {
+        let timestamp = now_millis();
+        let metrics: Vec<String> = metrics(stats).into_iter()
+            .filter(|(_, value)| value.is_finite())
+            .map(|(key, value)| format!(
+                "{{\"key\":\"{}\",\"value\":{},\"timestamp\":{},\"step\":{}}}",
+                key, value, timestamp, stats.generation
+            ))
+            .collect();
+
+        self.post("runs/log-batch", &format!(
+            "{{\"run_id\":{},\"metrics\":[{}]}}",
+            quote(&self.run_id),
+            metrics.join(",")
+        ))?;
+        Ok(())
+    }
+
+    // Marks the run finished.
+    pub fn finish(&self) -> Result<(), Error> {
+        self.post("runs/update", &format!(
+            "{{\"run_id\":{},\"status\":\"FINISHED\",\"end_time\":{}}}",
+            quote(&self.run_id),
+            now_millis()
+        ))?;
+        Ok(())
+    }
+
+    // A subscriber that logs every Event::GenerationCompleted and finishes
+    // the run on Event::RunFinished. Requests are best-effort; a failing
+    // server does not stop the run.
+    pub fn into_subscriber(self) -> Subscriber {
+        Arc::new(Mutex::new(move |event: &Event| match event {
+            Event::GenerationCompleted(stats) => {
+                let _ = self.log(stats);
+            }
+            Event::RunFinished(_) => {
+                let _ = self.finish();
+            }
+            _ => {}
+        }))
+    }
+
+    // POSTs `body` to the REST endpoint and returns the response body.
+    fn post(&self, endpoint: &str, body: &str) -> Result<String, Error> {
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port))?;
+        write!(
+            stream,
+            "POST {}/api/2.0/mlflow/{} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.prefix,
+            endpoint,
+            self.host,
+            self.port,
+            body.len(),
+            body
+        )?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        let status = response.split_whitespace().nth(1).unwrap_or("");
+        let body = response.split_once("\r\n\r\n").map_or("", |(_, body)| body);
+        if !status.starts_with('2') {
+            return Err(failure(format!("{} returned {}: {}", endpoint, status, body.trim())));
+        }
+        Ok(body.to_string())
+    }
+}
+
+fn failure(reason: String) -> Error {
+    Error::Io(io::Error::other(reason))
+}
+
+fn now_millis() -> u128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis())
+}
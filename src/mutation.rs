@@ -0,0 +1,250 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use rand::{Rng, RngCore};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+
+use crate::error::Error;
+
+// Perturbs a permutation in place. Like CrossoverOperator, operators take a
+// type-erased RNG so they can be stored as shared trait objects.
+pub trait MutationOperator: Send + Sync {
+    fn mutate(&self, order: &mut [usize], rng: &mut dyn RngCore);
+}
+
+// Exchanges two random cities.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Swap;
+
+impl MutationOperator for Swap {
+    fn mutate(&self, order: &mut [usize], rng: &mut dyn RngCore) {
+        if order.is_empty() {
+            return;
+        }
+
+        let point_one = rng.gen_range(0, order.len());
+        let point_two = rng.gen_range(0, order.len());
+        order.swap(point_one, point_two);
+    }
+}
+
+// Reverses a random segment, the 2-opt move: only the two edges at the
+// segment's ends change, which is why it preserves tour structure so well.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Inversion;
+
+impl MutationOperator for Inversion {
+    fn mutate(&self, order: &mut [usize], rng: &mut dyn RngCore) {
+        if let Some((start, end)) = segment(order.len(), rng) {
+            order[start..=end].reverse();
+        }
+    }
+}
+
+// Moves one random city to another random position.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Insertion;
+
+impl MutationOperator for Insertion {
+    fn mutate(&self, order: &mut [usize], rng: &mut dyn RngCore) {
+        if order.is_empty() {
+            return;
+        }
+
+        let from = rng.gen_range(0, order.len());
+        let to = rng.gen_range(0, order.len());
+        if from < to {
+            order[from..=to].rotate_left(1);
+        } else {
+            order[to..=from].rotate_right(1);
+        }
+    }
+}
+
+// Shuffles a random segment.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Scramble;
+
+impl MutationOperator for Scramble {
+    fn mutate(&self, order: &mut [usize], rng: &mut dyn RngCore) {
+        if let Some((start, end)) = segment(order.len(), rng) {
+            order[start..=end].shuffle(rng);
+        }
+    }
+}
+
+// Applies one of several operators per mutation, chosen with probability
+// proportional to its weight.
+#[derive(Clone)]
+pub struct WeightedMix {
+    operators: Vec<Arc<dyn MutationOperator>>,
+    weights: WeightedIndex<f64>,
+}
+
+impl WeightedMix {
+    // Fails if `operators` is empty or a weight is negative or all are zero.
+    pub fn new(operators: Vec<(f64, Arc<dyn MutationOperator>)>) -> Result<WeightedMix, Error> {
+        let total: f64 = operators.iter().map(|(w, _)| *w).sum();
+        let weights = WeightedIndex::new(operators.iter().map(|(w, _)| *w))
+            .map_err(|_| Error::InvalidParameter { name: "mutation weights", value: total })?;
+
+        Ok(WeightedMix {
+            operators: operators.into_iter().map(|(_, op)| op).collect(),
+            weights,
+        })
+    }
+}
+
+impl MutationOperator for WeightedMix {
+    fn mutate(&self, order: &mut [usize], rng: &mut dyn RngCore) {
+        let chosen = self.weights.sample(rng);
+        self.operators[chosen].mutate(order, rng);
+    }
+}
+
+// The built-in single operators by name, for configuring a run from text.
+// Each variant mutates as its operator does.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mutation {
+    Swap,
+    Inversion,
+    Insertion,
+    Scramble,
+}
+
+impl Mutation {
+    pub const ALL: [Mutation; 4] = [Mutation::Swap, Mutation::Inversion, Mutation::Insertion, Mutation::Scramble];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Mutation::Swap => "swap",
+            Mutation::Inversion => "inversion",
+            Mutation::Insertion => "insertion",
+            Mutation::Scramble => "scramble",
+        }
+    }
+}
+
+impl MutationOperator for Mutation {
+    fn mutate(&self, order: &mut [usize], rng: &mut dyn RngCore) {
+        match self {
+            Mutation::Swap => Swap.mutate(order, rng),
+            Mutation::Inversion => Inversion.mutate(order, rng),
+            Mutation::Insertion => Insertion.mutate(order, rng),
+            Mutation::Scramble => Scramble.mutate(order, rng),
+        }
+    }
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Mutation {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Mutation, Error> {
+        Mutation::ALL.iter().copied()
+            .find(|operator| operator.name() == name)
+            .ok_or_else(|| Error::UnknownName { kind: "mutation operator", name: name.to_string() })
+    }
+}
+
+fn segment(n: usize, rng: &mut dyn RngCore) -> Option<(usize, usize)> {
+    if n < 2 {
+        return None;
+    }
+
+    let a = rng.gen_range(0, n);
+    let b = rng.gen_range(0, n);
+    Some((a.min(b), a.max(b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use rand::SeedableRng;
+    use rand::rngs::SmallRng;
+
+    use super::*;
+
+    fn is_permutation(order: &[usize]) -> bool {
+        let mut sorted = order.to_vec();
+        sorted.sort_unstable();
+        sorted.into_iter().eq(0..order.len())
+    }
+
+    #[derive(Default)]
+    struct Counting(AtomicUsize);
+
+    impl MutationOperator for Counting {
+        fn mutate(&self, _order: &mut [usize], _rng: &mut dyn RngCore) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn operators_preserve_the_permutation() {
+        let mix = WeightedMix::new(vec![(1.0, Arc::new(Inversion) as Arc<dyn MutationOperator>), (2.0, Arc::new(Scramble))]).unwrap();
+        let operators: [&dyn MutationOperator; 5] = [&Swap, &Inversion, &Insertion, &Scramble, &mix];
+        let mut rng = SmallRng::seed_from_u64(7);
+        for n in 0..10 {
+            for operator in &operators {
+                let mut order: Vec<usize> = (0..n).collect();
+                for _ in 0..100 {
+                    operator.mutate(&mut order, &mut rng);
+                    assert!(is_permutation(&order), "{:?}", order);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn short_orders_do_not_panic() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        for operator in [&Inversion as &dyn MutationOperator, &Insertion, &Scramble] {
+            operator.mutate(&mut [], &mut rng);
+            let mut one = [0];
+            operator.mutate(&mut one, &mut rng);
+            assert_eq!(one, [0]);
+            let mut two = [0, 1];
+            operator.mutate(&mut two, &mut rng);
+            assert!(two == [0, 1] || two == [1, 0]);
+        }
+    }
+
+    #[test]
+    fn weighted_mix_rejects_empty_or_zero_weights() {
+        assert!(matches!(WeightedMix::new(Vec::new()), Err(Error::InvalidParameter { .. })));
+        let zero = vec![(0.0, Arc::new(Swap) as Arc<dyn MutationOperator>), (0.0, Arc::new(Inversion))];
+        assert!(matches!(WeightedMix::new(zero), Err(Error::InvalidParameter { .. })));
+        let negative = vec![(-1.0, Arc::new(Swap) as Arc<dyn MutationOperator>), (2.0, Arc::new(Inversion))];
+        assert!(matches!(WeightedMix::new(negative), Err(Error::InvalidParameter { .. })));
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for &operator in &Mutation::ALL {
+            assert_eq!(operator.to_string().parse::<Mutation>().unwrap(), operator);
+        }
+        assert!(matches!("flip".parse::<Mutation>(), Err(Error::UnknownName { .. })));
+    }
+
+    #[test]
+    fn weighted_mix_never_picks_a_zero_weight() {
+        let never = Arc::new(Counting::default());
+        let always = Arc::new(Counting::default());
+        let mix = WeightedMix::new(vec![(0.0, never.clone() as Arc<dyn MutationOperator>), (1.0, always.clone())]).unwrap();
+        let mut rng = SmallRng::seed_from_u64(2);
+        for _ in 0..100 {
+            mix.mutate(&mut [0, 1, 2], &mut rng);
+        }
+        assert_eq!(never.0.load(Ordering::Relaxed), 0);
+        assert_eq!(always.0.load(Ordering::Relaxed), 100);
+    }
+}
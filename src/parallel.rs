@@ -0,0 +1,99 @@
+use std::panic;
+use std::thread;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::SmallRng;
+
+use crate::chromosome::Chromosome;
+
+// One member of the next generation: a copy of `mother`, or her child with
+// `father`, optionally mutated. Each job carries its own seed so the result
+// is the same however the jobs are spread over threads.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Job {
+    pub(crate) mother: usize,
+    pub(crate) father: Option<usize>,
+    pub(crate) mutate: bool,
+    pub(crate) seed: u64,
+}
+
+impl Job {
+    pub(crate) fn new<R: Rng>(mother: usize, father: Option<usize>, mutation_rate: f64, rng: &mut R) -> Job {
+        Job { mother, father, mutate: rng.gen_bool(mutation_rate), seed: rng.gen() }
+    }
+
+    // An unchanged copy of `mother`.
+    pub(crate) fn elite(mother: usize) -> Job {
+        Job { mother, father: None, mutate: false, seed: 0 }
+    }
+
+    // Returns the child with its fitness before mutation, so improvements can
+    // be credited to crossover or mutation.
+    fn run<C: Chromosome>(&self, population: &[C], problem: &C::Problem) -> (C, f64) {
+        let mut rng = SmallRng::seed_from_u64(self.seed);
+        let mother = &population[self.mother];
+        let mut child = match self.father {
+            Some(father) => mother.crossover(&population[father], problem, &mut rng),
+            None => mother.clone(),
+        };
+        let unmutated = child.fitness();
+
+        if self.mutate {
+            child.mutate(problem, &mut rng);
+        }
+
+        (child, unmutated)
+    }
+}
+
+// Builds the next generation from `jobs`. Stored as a function pointer on the
+// simulation so the threaded version, which needs Send/Sync bounds, can be
+// chosen without putting those bounds on every Simulation.
+pub(crate) type Breeder<C> = fn(&[C], &<C as Chromosome>::Problem, &[Job], usize) -> Vec<(C, f64)>;
+
+pub(crate) fn breed_serial<C: Chromosome>(
+    population: &[C],
+    problem: &C::Problem,
+    jobs: &[Job],
+    _threads: usize,
+) -> Vec<(C, f64)> {
+    jobs.iter().map(|job| job.run(population, problem)).collect()
+}
+
+pub(crate) fn breed_parallel<C>(
+    population: &[C],
+    problem: &C::Problem,
+    jobs: &[Job],
+    threads: usize,
+) -> Vec<(C, f64)>
+where
+    C: Chromosome + Send + Sync,
+    C::Problem: Sync,
+{
+    map_chunks(jobs, threads, |job| job.run(population, problem))
+}
+
+// Applies `f` to every item, splitting the items into one contiguous chunk
+// per thread and keeping the output in input order.
+pub(crate) fn map_chunks<T, U, F>(items: &[T], threads: usize, f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync,
+{
+    if threads <= 1 || items.len() <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let chunk = items.len().div_ceil(threads);
+    let f = &f;
+    thread::scope(|scope| {
+        let handles: Vec<_> = items.chunks(chunk)
+            .map(|part| scope.spawn(move || part.iter().map(f).collect::<Vec<U>>()))
+            .collect();
+
+        handles.into_iter()
+            .flat_map(|h| h.join().unwrap_or_else(|payload| panic::resume_unwind(payload)))
+            .collect()
+    })
+}
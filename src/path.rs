@@ -0,0 +1,267 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+
+use crate::chromosome::{Chromosome, Distance, Fnv};
+use crate::city::City;
+use crate::cost::{visited_cities, CostModel};
+use crate::crossover::{CrossoverOperator, SinglePoint};
+use crate::error::Error;
+use crate::local_search::{or_opt, two_opt, MAX_PASSES};
+use crate::matrix::DistanceMatrix;
+use crate::metric::DistanceMetric;
+use crate::mutation::{MutationOperator, Swap};
+use crate::parallel::map_chunks;
+
+// The travelling salesman problem as seen by a Path: the cities to visit, how
+// a tour over them is costed, and the operators that vary tours.
+#[derive(Clone)]
+pub struct TspProblem {
+    pub city_list: Vec<City>,
+    pub matrix: Arc<DistanceMatrix>,
+    pub cost_model: CostModel,
+    pub crossover: Arc<dyn CrossoverOperator>,
+    pub mutation: Arc<dyn MutationOperator>,
+}
+
+impl TspProblem {
+    pub fn new(city_list: Vec<City>) -> TspProblem {
+        TspProblem {
+            matrix: Arc::new(DistanceMatrix::new(&city_list)),
+            city_list,
+            cost_model: CostModel::default(),
+            crossover: Arc::new(SinglePoint),
+            mutation: Arc::new(Swap),
+        }
+    }
+
+    // Measures edges with `metric` instead of Euclidean distance.
+    pub fn with_metric(city_list: Vec<City>, metric: &dyn DistanceMetric) -> TspProblem {
+        TspProblem { matrix: Arc::new(DistanceMatrix::with_metric(&city_list, metric)), ..TspProblem::new(city_list) }
+    }
+}
+
+#[derive(Clone)]
+pub struct Path {
+    pub(crate) fitness: f64,
+    pub(crate) order: Vec<usize>
+}
+
+impl Path {
+    // Crosses with `other` using the problem's crossover operator.
+    pub fn breed<R: Rng>(&self, other: &Path, problem: &TspProblem, rng: &mut R) -> Path {
+        self.breed_with(&*problem.crossover, other, problem, rng)
+    }
+
+    pub fn breed_with<R: Rng>(
+        &self,
+        operator: &dyn CrossoverOperator,
+        other: &Path,
+        problem: &TspProblem,
+        rng: &mut R,
+    ) -> Path {
+        let order = operator.crossover(&self.order, &other.order, rng);
+        let fitness = Path::calculate_fitness(&order, &problem.city_list, &problem.matrix, problem.cost_model);
+
+        Path { fitness, order }
+    }
+
+    // Mutates using the problem's mutation operator.
+    pub fn mutate<R: Rng>(&mut self, problem: &TspProblem, rng: &mut R) {
+        self.mutate_with(&*problem.mutation, problem, rng);
+    }
+
+    pub fn mutate_with<R: Rng>(&mut self, operator: &dyn MutationOperator, problem: &TspProblem, rng: &mut R) {
+        operator.mutate(&mut self.order, rng);
+        self.fitness = Path::calculate_fitness(&self.order, &problem.city_list, &problem.matrix, problem.cost_model);
+    }
+
+    pub fn from_order(order: Vec<usize>, problem: &TspProblem) -> Result<Path, Error> {
+        let city_count = problem.city_list.len();
+        if !is_permutation(&order, city_count) {
+            return Err(Error::InvalidTour(format!("not a permutation of {} cities", city_count)));
+        }
+
+        let fitness = Path::calculate_fitness(&order, &problem.city_list, &problem.matrix, problem.cost_model);
+        Ok(Path { fitness, order })
+    }
+
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    pub fn fitness(&self) -> f64 {
+        self.fitness
+    }
+
+    // The cities the tour actually stops at: the full order unless some
+    // optional cities are skipped by the decoder.
+    pub fn visited(&self, problem: &TspProblem) -> Vec<usize> {
+        visited_cities(&self.order, &problem.city_list, &problem.matrix, problem.cost_model)
+            .unwrap_or_else(|| self.order.clone())
+    }
+
+    // Tour cost under the cost model the path was evaluated with.
+    pub fn cost(&self) -> f64 {
+        1.0 / self.fitness
+    }
+
+    pub fn calculate_fitness(
+        path: &[usize],
+        city_list: &[City],
+        matrix: &DistanceMatrix,
+        cost_model: CostModel,
+    ) -> f64 {
+        1.0 / cost_model.tour_cost(path, city_list, matrix)
+    }
+
+    // Applies 2-opt and Or-opt passes until neither shortens the tour, then
+    // re-evaluates it. Returns whether the path improved; a result that the
+    // cost model scores worse than the original, as can happen with optional
+    // cities or an asymmetric matrix, is undone.
+    pub fn improve(&mut self, problem: &TspProblem) -> bool {
+        let tour_type = problem.cost_model.tour_type;
+        let mut order = self.order.clone();
+        for _ in 0..MAX_PASSES {
+            let reversed = two_opt(&mut order, &problem.matrix, tour_type);
+            let moved = or_opt(&mut order, &problem.matrix, tour_type);
+            if !reversed && !moved {
+                break;
+            }
+        }
+
+        let fitness = Path::calculate_fitness(&order, &problem.city_list, &problem.matrix, problem.cost_model);
+        if fitness > self.fitness {
+            self.order = order;
+            self.fitness = fitness;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Number of this path's edges, taken as undirected and including the
+    // one closing the tour, that `other` does not use. Rotations and
+    // reversals of the same cycle are at distance zero.
+    pub fn edge_distance(&self, other: &Path) -> usize {
+        let shared: std::collections::HashSet<(usize, usize)> = tour_edges(&other.order).collect();
+        tour_edges(&self.order).filter(|edge| !shared.contains(edge)).count()
+    }
+}
+
+// The undirected edges of the closed tour through `order`, each as
+// (smaller, larger) city index.
+fn tour_edges(order: &[usize]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let closing = match (order.first(), order.last()) {
+        (Some(&first), Some(&last)) if order.len() > 2 => Some([last, first]),
+        _ => None,
+    };
+    order.windows(2)
+        .map(|w| [w[0], w[1]])
+        .chain(closing)
+        .map(|[a, b]| (a.min(b), a.max(b)))
+}
+
+impl Chromosome for Path {
+    type Problem = TspProblem;
+
+    fn fitness(&self) -> f64 {
+        self.fitness
+    }
+
+    fn crossover<R: Rng>(&self, other: &Path, problem: &TspProblem, rng: &mut R) -> Path {
+        self.breed(other, problem, rng)
+    }
+
+    fn mutate<R: Rng>(&mut self, problem: &TspProblem, rng: &mut R) {
+        Path::mutate(self, problem, rng);
+    }
+
+    fn evaluate(&mut self, problem: &TspProblem) {
+        self.fitness = Path::calculate_fitness(&self.order, &problem.city_list, &problem.matrix, problem.cost_model);
+    }
+
+    // Equal fitness falls back to the visiting order so the ranking never
+    // depends on where a path happened to sit in the population.
+    fn tie_break(&self, other: &Path) -> Ordering {
+        self.order.cmp(&other.order)
+    }
+
+    fn fingerprint(&self) -> u64 {
+        let mut hash = Fnv::new();
+        for city in &self.order {
+            hash.feed(*city as u64);
+        }
+        hash.finish()
+    }
+}
+
+impl Distance for Path {
+    fn distance(&self, other: &Path) -> f64 {
+        self.edge_distance(other) as f64
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(& self, f: &mut fmt::Formatter) -> fmt::Result {
+        let string_order: Vec<String> = self.order.iter().map(|i| i.to_string()).collect();
+        write!(f, "Fitness: {}, Path: {}", self.fitness, string_order.join("->"))
+    }
+}
+
+pub fn initial_population<R: Rng>(city_list: &[City], population_count: usize, rng: &mut R) -> Vec<Path> {
+    initial_population_with_threads(city_list, population_count, 1, rng)
+}
+
+// Shuffles and evaluates the initial tours on `threads` threads. Each tour
+// uses its own seed drawn from `rng`, so the population is the same for any
+// thread count.
+pub fn initial_population_with_threads<R: Rng>(
+    city_list: &[City],
+    population_count: usize,
+    threads: usize,
+    rng: &mut R,
+) -> Vec<Path> {
+    let base_list: Vec<usize> = (0..city_list.len()).collect();
+    let seeds: Vec<u64> = (0..population_count).map(|_| rng.gen()).collect();
+    let matrix = DistanceMatrix::new(city_list);
+
+    map_chunks(&seeds, threads, |&seed| {
+        let mut p = base_list.clone();
+        p.shuffle(&mut SmallRng::seed_from_u64(seed));
+        let fitness = Path::calculate_fitness(&p, city_list, &matrix, CostModel::default());
+
+        Path { fitness, order: p }
+    })
+}
+
+pub(crate) fn is_permutation(order: &[usize], city_count: usize) -> bool {
+    let mut seen = vec![false; city_count];
+    order.len() == city_count && order.iter().all(|&c| c < city_count && !std::mem::replace(&mut seen[c], true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(order: &[usize]) -> Path {
+        Path { fitness: 1.0, order: order.to_vec() }
+    }
+
+    #[test]
+    fn rotations_and_reversals_of_a_tour_are_at_distance_zero() {
+        let tour = path(&[0, 1, 2, 3, 4]);
+        assert_eq!(tour.edge_distance(&path(&[2, 3, 4, 0, 1])), 0);
+        assert_eq!(tour.edge_distance(&path(&[1, 0, 4, 3, 2])), 0);
+    }
+
+    #[test]
+    fn the_closing_edge_is_counted() {
+        // 1-2 and the closing 3-0 are missing from the other tour.
+        assert_eq!(path(&[0, 1, 2, 3]).edge_distance(&path(&[0, 1, 3, 2])), 2);
+    }
+}
@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use rand::{FromEntropy, Rng, SeedableRng};
+use rand::rngs::SmallRng;
+
+use crate::chromosome::Chromosome;
+use crate::crossover::{CrossoverOperator, OrderCrossover};
+use crate::error::Error;
+use crate::simulation::Simulation;
+
+// Settings for evolve. `crossover` builds a child from two parents; the
+// default takes each gene from either parent with equal odds, which breaks
+// orderings, so set it to order_crossover when genomes are permutations.
+// `mutate` changes a genome in place; the default swaps two random genes,
+// which keeps a permutation one. Any non-capturing closure fits either,
+// e.g. a mutation that nudges a random real-valued gene.
+pub struct EvolveConfig<T> {
+    pub generations: usize,
+    pub crossover_rate: f64,
+    pub mutation_rate: f64,
+    pub survival_rate: f64,
+    pub elitism: usize,
+    pub seed: Option<u64>,
+    pub crossover: fn(&[T], &[T], &mut SmallRng) -> Vec<T>,
+    pub mutate: fn(&mut [T], &mut SmallRng),
+}
+
+impl<T: Clone> Default for EvolveConfig<T> {
+    fn default() -> EvolveConfig<T> {
+        EvolveConfig {
+            generations: 100,
+            crossover_rate: 0.8,
+            mutation_rate: 0.05,
+            survival_rate: 0.2,
+            elitism: 1,
+            seed: None,
+            crossover: uniform_genes,
+            mutate: swap_genes,
+        }
+    }
+}
+
+// The fittest genome evolve found and the generation it appeared in.
+#[derive(Clone, Debug)]
+pub struct Best<T> {
+    pub genome: Vec<T>,
+    pub fitness: f64,
+    pub generation: usize,
+}
+
+// Evolves `initial` towards higher `fitness` and returns the best genome,
+// without implementing Chromosome. For tours over cities Path and
+// Simulation offer more operators and local search.
+pub fn evolve<T, F>(initial: Vec<Vec<T>>, fitness: F, config: EvolveConfig<T>) -> Result<Best<T>, Error>
+where
+    T: Clone + 'static,
+    F: Fn(&[T]) -> f64 + 'static,
+{
+    let length = initial.first().ok_or(Error::EmptyPopulation)?.len();
+    if let Some(genome) = initial.iter().find(|g| g.len() != length) {
+        return Err(Error::LengthMismatch { expected: length, found: genome.len() });
+    }
+
+    let problem = VecProblem { fitness: Arc::new(fitness), crossover: config.crossover, mutate: config.mutate };
+    let population = initial.into_iter()
+        .map(|genes| VecGenome { fitness: (problem.fitness)(&genes), genes })
+        .collect();
+    let rng = match config.seed {
+        Some(seed) => SmallRng::seed_from_u64(seed),
+        None => SmallRng::from_entropy(),
+    };
+
+    let mut sim = Simulation::with_problem(
+        population,
+        problem,
+        config.generations,
+        config.crossover_rate,
+        config.mutation_rate,
+        config.survival_rate,
+        rng,
+    )?.with_elitism(config.elitism)?;
+    let result = sim.run();
+
+    Ok(Best { genome: result.best.genes, fitness: result.fitness, generation: result.generation })
+}
+
+// OX1 of two parents that hold the same genes in different orders, so the
+// child is an ordering of them too. Parents that are not permutations of
+// each other yield a copy of the mother.
+pub fn order_crossover<T: Clone + PartialEq>(mother: &[T], father: &[T], rng: &mut SmallRng) -> Vec<T> {
+    // The father as positions in the mother, each position used once so
+    // repeated genes are matched in turn.
+    let mut used = vec![false; mother.len()];
+    let mut order = Vec::with_capacity(father.len());
+    for gene in father {
+        match (0..mother.len()).find(|&i| !used[i] && mother[i] == *gene) {
+            Some(i) => {
+                used[i] = true;
+                order.push(i);
+            }
+            None => return mother.to_vec(),
+        }
+    }
+    if order.len() != mother.len() {
+        return mother.to_vec();
+    }
+
+    let identity: Vec<usize> = (0..mother.len()).collect();
+    OrderCrossover.crossover(&identity, &order, rng).into_iter().map(|i| mother[i].clone()).collect()
+}
+
+fn uniform_genes<T: Clone>(mother: &[T], father: &[T], rng: &mut SmallRng) -> Vec<T> {
+    mother.iter().zip(father)
+        .map(|(a, b)| if rng.gen() { a.clone() } else { b.clone() })
+        .collect()
+}
+
+fn swap_genes<T>(genes: &mut [T], rng: &mut SmallRng) {
+    if genes.len() > 1 {
+        genes.swap(rng.gen_range(0, genes.len()), rng.gen_range(0, genes.len()));
+    }
+}
+
+type Fitness<T> = Arc<dyn Fn(&[T]) -> f64>;
+
+#[derive(Clone)]
+struct VecProblem<T> {
+    fitness: Fitness<T>,
+    crossover: fn(&[T], &[T], &mut SmallRng) -> Vec<T>,
+    mutate: fn(&mut [T], &mut SmallRng),
+}
+
+#[derive(Clone)]
+struct VecGenome<T> {
+    fitness: f64,
+    genes: Vec<T>,
+}
+
+impl<T: Clone> Chromosome for VecGenome<T> {
+    type Problem = VecProblem<T>;
+
+    fn fitness(&self) -> f64 {
+        self.fitness
+    }
+
+    fn crossover<R: Rng>(&self, other: &VecGenome<T>, problem: &VecProblem<T>, rng: &mut R) -> VecGenome<T> {
+        let mut rng = SmallRng::seed_from_u64(rng.gen());
+        let genes = (problem.crossover)(&self.genes, &other.genes, &mut rng);
+        VecGenome { fitness: (problem.fitness)(&genes), genes }
+    }
+
+    fn mutate<R: Rng>(&mut self, problem: &VecProblem<T>, rng: &mut R) {
+        let mut rng = SmallRng::seed_from_u64(rng.gen());
+        (problem.mutate)(&mut self.genes, &mut rng);
+        self.fitness = (problem.fitness)(&self.genes);
+    }
+
+    fn evaluate(&mut self, problem: &VecProblem<T>) {
+        self.fitness = (problem.fitness)(&self.genes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::seq::SliceRandom;
+
+    use super::*;
+
+    fn is_ordering(genes: &[char]) -> bool {
+        let mut sorted = genes.to_vec();
+        sorted.sort_unstable();
+        sorted == "abcdefgh".chars().collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn order_crossover_keeps_orderings() {
+        let mother: Vec<char> = "abcdefgh".chars().collect();
+        let father: Vec<char> = "hgfedcba".chars().collect();
+        let mut rng = SmallRng::seed_from_u64(3);
+        for _ in 0..50 {
+            assert!(is_ordering(&order_crossover(&mother, &father, &mut rng)));
+        }
+        assert_eq!(order_crossover(&mother, &mother[1..], &mut rng), mother);
+        assert_eq!(order_crossover(&mother, &"abcdefgz".chars().collect::<Vec<_>>(), &mut rng), mother);
+    }
+
+    #[test]
+    fn evolved_orderings_stay_orderings() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let initial: Vec<Vec<char>> = (0..20).map(|_| {
+            let mut genes: Vec<char> = "abcdefgh".chars().collect();
+            genes.shuffle(&mut rng);
+            genes
+        }).collect();
+        // Rewards letters that are in alphabetical position.
+        let fitness = |genes: &[char]| genes.iter().zip("abcdefgh".chars()).filter(|(a, b)| **a == *b).count() as f64;
+        let config = EvolveConfig { seed: Some(2), crossover: order_crossover, mutation_rate: 0.3, ..EvolveConfig::default() };
+        let best = evolve(initial, fitness, config).unwrap();
+        assert!(is_ordering(&best.genome));
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt::Write;
+
+use crate::city::City;
+use crate::cost::TourType;
+use crate::path::{Path, TspProblem};
+
+// Side of the drawing area in SVG user units; the instance is scaled to fit.
+const SIZE: f64 = 800.0;
+const MARGIN: f64 = 20.0;
+
+// Draws every city as a dot and `tour` as a line through its cities in
+// order, closed back to the start for a closed tour. Cities the tour does
+// not visit are drawn hollow. `caption`, if any, is printed at the top left.
+pub fn tour_svg(cities: &[City], tour: &[usize], tour_type: TourType, caption: Option<&str>) -> String {
+    let (min_x, max_x) = bounds(cities.iter().map(|c| c.x));
+    let (min_y, max_y) = bounds(cities.iter().map(|c| c.y));
+    let span = (max_x - min_x).max(max_y - min_y);
+    let scale = if span > 0.0 { (SIZE - 2.0 * MARGIN) / span } else { 1.0 };
+    // SVG's y axis points down, so y is flipped to keep the usual orientation.
+    let point = |c: &City| (MARGIN + (c.x - min_x) * scale, SIZE - MARGIN - (c.y - min_y) * scale);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" viewBox=\"0 0 {0} {0}\">",
+        SIZE
+    );
+    let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>");
+
+    let points: Vec<String> = tour.iter()
+        .map(|&i| point(&cities[i]))
+        .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+        .collect();
+    let shape = if tour_type == TourType::Closed { "polygon" } else { "polyline" };
+    let _ = writeln!(
+        svg,
+        "<{} points=\"{}\" fill=\"none\" stroke=\"steelblue\" stroke-width=\"2\"/>",
+        shape,
+        points.join(" ")
+    );
+
+    let mut visited = vec![false; cities.len()];
+    for &i in tour {
+        visited[i] = true;
+    }
+    for (city, visited) in cities.iter().zip(visited) {
+        let (x, y) = point(city);
+        let fill = if visited { "black" } else { "white" };
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"4\" fill=\"{}\" stroke=\"black\"/>",
+            x, y, fill
+        );
+    }
+
+    if let Some(caption) = caption {
+        let text = caption.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        let _ = writeln!(svg, "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"14\">{}</text>", MARGIN, MARGIN, text);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// The stops `path` makes under `problem`, captioned with its cost.
+pub fn path_svg(path: &Path, problem: &TspProblem) -> String {
+    let caption = format!("cost {}", path.cost());
+    tour_svg(&problem.city_list, &path.visited(problem), problem.cost_model.tour_type, Some(&caption))
+}
+
+fn bounds<I: Iterator<Item = f64>>(values: I) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(v), max.max(v)))
+}
@@ -0,0 +1,76 @@
+use std::cmp::Ordering;
+
+use rand::Rng;
+
+use crate::chromosome::{rank, Chromosome};
+use crate::error::Error;
+use crate::simulation::{Simulation, SimulationResult};
+
+#[derive(Clone, Copy, Debug)]
+pub struct ConfidenceInterval {
+    pub estimate: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub confidence: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct MultiStartReport<C> {
+    pub results: Vec<SimulationResult<C>>,
+    pub best: SimulationResult<C>,
+    // Bootstrap interval for the best cost achievable with this many restarts.
+    pub best_cost: ConfidenceInterval,
+}
+
+// Runs `runs` independent simulations built by `make` (given the restart
+// index; construction errors are passed through) and reports the overall
+// best together with a bootstrap confidence interval for the best cost.
+pub fn multi_start<C, R, F, G>(
+    runs: usize,
+    mut make: F,
+    resamples: usize,
+    confidence: f64,
+    rng: &mut G,
+) -> Result<MultiStartReport<C>, Error>
+where
+    C: Chromosome,
+    R: Rng,
+    F: FnMut(usize) -> Result<Simulation<C, R>, Error>,
+    G: Rng,
+{
+    let mut results: Vec<SimulationResult<C>> = Vec::with_capacity(runs);
+    for i in 0..runs {
+        results.push(make(i)?.run());
+    }
+
+    let best = results.iter()
+        .min_by(|a, b| rank(&a.best, &b.best))
+        .cloned()
+        .ok_or(Error::InvalidParameter { name: "runs", value: 0.0 })?;
+    let costs: Vec<f64> = results.iter().map(|r| r.cost).collect();
+    let best_cost = bootstrap_min(&costs, resamples, confidence, rng);
+
+    Ok(MultiStartReport { results, best, best_cost })
+}
+
+// Percentile bootstrap interval for the minimum of `samples`: resample with
+// replacement `resamples` times, take each resample's minimum, and read off
+// the central `confidence` share of those minima.
+pub fn bootstrap_min<R: Rng>(samples: &[f64], resamples: usize, confidence: f64, rng: &mut R) -> ConfidenceInterval {
+    let estimate = samples.iter().cloned().fold(f64::INFINITY, f64::min);
+    if samples.is_empty() || resamples == 0 {
+        return ConfidenceInterval { estimate, lower: estimate, upper: estimate, confidence };
+    }
+
+    let mut minima: Vec<f64> = (0..resamples).map(|_| {
+        (0..samples.len())
+            .map(|_| samples[rng.gen_range(0, samples.len())])
+            .fold(f64::INFINITY, f64::min)
+    }).collect();
+    minima.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    let tail = (1.0 - confidence) / 2.0;
+    let at = |q: f64| minima[((q * (resamples - 1) as f64).round() as usize).min(resamples - 1)];
+
+    ConfidenceInterval { estimate, lower: at(tail), upper: at(1.0 - tail), confidence }
+}
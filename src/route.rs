@@ -0,0 +1,49 @@
+use std::fmt;
+
+use crate::cost::TourType;
+use crate::path::{Path, TspProblem};
+
+// One city on a route, named when the city list names it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stop {
+    pub city: usize,
+    pub name: Option<String>,
+}
+
+// A tour as the cities it stops at, in order, and what it costs. Its
+// Display reads "Berlin -> Hamburg -> Munich -> Berlin", returning to the
+// start for a closed tour; cities without a name appear as their index.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Route {
+    pub stops: Vec<Stop>,
+    pub cost: f64,
+    pub tour_type: TourType,
+}
+
+impl Route {
+    pub fn of(path: &Path, problem: &TspProblem) -> Route {
+        let stops = path.visited(problem).into_iter()
+            .map(|city| Stop { city, name: problem.city_list[city].name.clone() })
+            .collect();
+        Route { stops, cost: path.cost(), tour_type: problem.cost_model.tour_type }
+    }
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "{}", self.city),
+        }
+    }
+}
+
+impl fmt::Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut stops: Vec<String> = self.stops.iter().map(Stop::to_string).collect();
+        if let (TourType::Closed, Some(start)) = (self.tour_type, stops.first()) {
+            stops.push(start.clone());
+        }
+        write!(f, "{}", stops.join(" -> "))
+    }
+}
@@ -0,0 +1,130 @@
+use std::cmp::Ordering;
+
+use rand::Rng;
+use rand::distributions::{Distribution, Uniform};
+
+use crate::chromosome::{rank, Chromosome};
+
+// How parents are chosen for crossover.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Selection {
+    // Parents come from the fittest crossover_rate share of the population,
+    // which is ranked every generation.
+    #[default]
+    Truncation,
+    // Each parent is the fittest of `size` members drawn at random, with
+    // replacement, from the whole population. Nothing is sorted: the
+    // retained members are found by partial selection, so choosing a
+    // generation costs O(n) rather than O(n log n) for huge populations.
+    Tournament { size: usize },
+    // Fitness uniform selection (FUSS), experimental: each parent is the
+    // member whose fitness is closest to a value drawn uniformly between
+    // the worst and best fitness, ties broken at random. Rare fitness
+    // levels are picked as often as crowded ones, which keeps diversity up
+    // on deceptive problems at the cost of slower convergence.
+    FitnessUniform,
+}
+
+// Which partner each selected mother breeds with. Partners are drawn the
+// way the Selection draws parents; the policies differ in which draws they
+// reject or prefer.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Mating {
+    // The first partner drawn, which may be the mother herself and then
+    // yields a clone.
+    #[default]
+    Random,
+    // Redraws while the partner is the mother herself.
+    AvoidSelf,
+    // Redraws while the partner's genes are identical to the mother's
+    // (structural distance zero), which also rules out self-pairing.
+    AvoidIdentical,
+    // Negative assortative mating: draws `candidates` partners and takes the
+    // one most distant from the mother, preserving diversity.
+    Dissimilar { candidates: usize },
+}
+
+// How offspring enter the population.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Replacement {
+    // Every step breeds a whole new generation.
+    #[default]
+    Generational,
+    // Every step breeds `offspring` children, parents drawn as the Selection
+    // and Mating choose them, and overwrites the weakest members in place;
+    // elites are never overwritten. Each step counts as a generation, so
+    // max_iterations and the other generation counts count steps.
+    SteadyState { offspring: usize },
+}
+
+// Redraws allowed before AvoidSelf or AvoidIdentical settle for the last
+// partner drawn, as when the whole pool shares one genome.
+pub(crate) const MAX_REDRAWS: usize = 10;
+
+// What one generation's parents are drawn from, set up once per generation.
+pub(crate) enum Pool {
+    // The ranked breeding pool at the front of the population.
+    Ranked(Uniform<usize>),
+    Tournament { size: usize },
+    // Member indices and their fitness, in ascending order of fitness.
+    FitnessLevels { order: Vec<usize>, fitness: Vec<f64> },
+}
+
+impl Pool {
+    pub(crate) fn new<C: Chromosome>(selection: Selection, population: &[C], breeding_count: usize) -> Pool {
+        match selection {
+            Selection::Truncation => Pool::Ranked(Uniform::new(0, breeding_count)),
+            Selection::Tournament { size } => Pool::Tournament { size },
+            Selection::FitnessUniform => {
+                let mut order: Vec<usize> = (0..population.len()).collect();
+                order.sort_by(|&a, &b| population[a].fitness().partial_cmp(&population[b].fitness()).unwrap_or(Ordering::Equal));
+                let fitness = order.iter().map(|&i| population[i].fitness()).collect();
+                Pool::FitnessLevels { order, fitness }
+            }
+        }
+    }
+
+    pub(crate) fn draw<C: Chromosome, R: Rng>(&self, population: &[C], rng: &mut R) -> usize {
+        match self {
+            Pool::Ranked(range) => range.sample(rng),
+            Pool::Tournament { size } => tournament(population, *size, rng),
+            Pool::FitnessLevels { order, fitness } => {
+                let (worst, best) = (fitness[0], fitness[fitness.len() - 1]);
+                if best.partial_cmp(&worst) != Some(Ordering::Greater) {
+                    return order[rng.gen_range(0, order.len())];
+                }
+
+                let target = rng.gen_range(worst, best);
+                let above = fitness.partition_point(|&f| f < target);
+                let nearest = if above == 0 || fitness[above] - target < target - fitness[above - 1] {
+                    fitness[above]
+                } else {
+                    fitness[above - 1]
+                };
+                let first = fitness.partition_point(|&f| f < nearest);
+                let last = fitness.partition_point(|&f| f <= nearest);
+                order[rng.gen_range(first, last)]
+            }
+        }
+    }
+}
+
+// Index of the fittest of `size` randomly drawn members.
+pub(crate) fn tournament<C: Chromosome, R: Rng>(population: &[C], size: usize, rng: &mut R) -> usize {
+    let mut winner = rng.gen_range(0, population.len());
+    for _ in 1..size {
+        let challenger = rng.gen_range(0, population.len());
+        if rank(&population[challenger], &population[winner]) == Ordering::Less {
+            winner = challenger;
+        }
+    }
+    winner
+}
+
+// Moves the `k` fittest members to the front of `population`, in no
+// particular order, in linear time.
+pub(crate) fn partition_fittest<C: Chromosome>(population: &mut [C], k: usize) {
+    if k > 0 && k < population.len() {
+        population.select_nth_unstable_by(k - 1, rank);
+    }
+}
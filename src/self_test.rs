@@ -0,0 +1,166 @@
+use std::f64::consts::PI;
+use std::time::Instant;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+
+use genetic::{
+    initial_population, initial_population_with_threads, instances, City, CrossoverOperator,
+    CycleCrossover, Insertion, Inversion, MutationOperator, OrderCrossover, PartiallyMapped, Path,
+    Scramble, Simulation, SinglePoint, Swap, TspProblem,
+};
+
+const SEED: u64 = 0x5e1f_7e57;
+
+struct Check {
+    name: String,
+    outcome: Result<String, String>,
+}
+
+// Runs every check, printing one line each, and returns whether all passed.
+pub fn run() -> bool {
+    let mut checks = Vec::new();
+    checks.extend(crossover_checks());
+    checks.extend(mutation_checks());
+    checks.push(known_optimum("ring of 12", instances::ring(12, 10.0), 2.0 * 12.0 * 10.0 * (PI / 12.0).sin()));
+    checks.push(known_optimum("3x4 grid", instances::grid(3, 4, 1.0), 12.0));
+    checks.push(reproducibility());
+    checks.push(throughput());
+
+    let mut passed = true;
+    for check in &checks {
+        match &check.outcome {
+            Ok(detail) if detail.is_empty() => println!("ok    {}", check.name),
+            Ok(detail) => println!("ok    {} {}", check.name, detail),
+            Err(reason) => {
+                passed = false;
+                println!("FAIL  {}: {}", check.name, reason);
+            }
+        }
+    }
+
+    println!();
+    println!("{} of {} checks passed", checks.iter().filter(|c| c.outcome.is_ok()).count(), checks.len());
+    passed
+}
+
+fn is_permutation(order: &[usize], size: usize) -> bool {
+    let mut sorted = order.to_vec();
+    sorted.sort_unstable();
+    sorted.iter().copied().eq(0..size)
+}
+
+fn random_order<R: Rng>(size: usize, rng: &mut R) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..size).collect();
+    order.shuffle(rng);
+    order
+}
+
+fn crossover_checks() -> Vec<Check> {
+    let operators: Vec<(&str, Box<dyn CrossoverOperator>)> = vec![
+        ("single-point", Box::new(SinglePoint)),
+        ("ox", Box::new(OrderCrossover)),
+        ("pmx", Box::new(PartiallyMapped)),
+        ("cx", Box::new(CycleCrossover)),
+    ];
+
+    operators.into_iter().map(|(name, operator)| {
+        let mut rng = SmallRng::seed_from_u64(SEED);
+        let outcome = (0..1000).try_for_each(|trial| {
+            let size = 1 + trial % 20;
+            let mother = random_order(size, &mut rng);
+            let father = random_order(size, &mut rng);
+            let child = operator.crossover(&mother, &father, &mut rng);
+            if is_permutation(&child, size) {
+                Ok(())
+            } else {
+                Err(format!("{:?} x {:?} gave {:?}", mother, father, child))
+            }
+        });
+        Check { name: format!("crossover {}", name), outcome: outcome.map(|_| String::new()) }
+    }).collect()
+}
+
+fn mutation_checks() -> Vec<Check> {
+    let operators: Vec<(&str, Box<dyn MutationOperator>)> = vec![
+        ("swap", Box::new(Swap)),
+        ("inversion", Box::new(Inversion)),
+        ("insertion", Box::new(Insertion)),
+        ("scramble", Box::new(Scramble)),
+    ];
+
+    operators.into_iter().map(|(name, operator)| {
+        let mut rng = SmallRng::seed_from_u64(SEED);
+        let outcome = (0..1000).try_for_each(|trial| {
+            let size = 1 + trial % 20;
+            let original = random_order(size, &mut rng);
+            let mut order = original.clone();
+            operator.mutate(&mut order, &mut rng);
+            if is_permutation(&order, size) {
+                Ok(())
+            } else {
+                Err(format!("{:?} became {:?}", original, order))
+            }
+        });
+        Check { name: format!("mutation {}", name), outcome: outcome.map(|_| String::new()) }
+    }).collect()
+}
+
+// A seeded run on an instance whose optimal closed tour is known.
+fn known_optimum(name: &str, cities: Vec<City>, optimum: f64) -> Check {
+    let mut rng = SmallRng::seed_from_u64(SEED);
+    let init = initial_population(&cities, 200, &mut rng);
+    let outcome = Simulation::with_rng(init, cities, 500, 0.8, 0.2, 0.2, rng)
+        .map_err(|err| err.to_string())
+        .and_then(|sim| {
+            let result = sim.with_crossover(OrderCrossover).with_mutation(Inversion).run();
+            if (result.cost - optimum).abs() < 1e-6 {
+                Ok(format!("(cost {:.4})", result.cost))
+            } else {
+                Err(format!("best cost {} but the optimum is {}", result.cost, optimum))
+            }
+        });
+
+    Check { name: format!("optimum {}", name), outcome }
+}
+
+// The same seed must give the same run for any thread count.
+fn reproducibility() -> Check {
+    let cities = instances::ring(30, 10.0);
+    let run = |threads: usize| {
+        let mut rng = SmallRng::seed_from_u64(SEED);
+        let init = initial_population_with_threads(&cities, 100, threads, &mut rng);
+        Simulation::with_rng(init, cities.clone(), 50, 0.8, 0.1, 0.2, rng)
+            .map(|sim| sim.with_threads(threads).run_until(|_| false).population_hash)
+    };
+
+    let outcome = match (run(1), run(1), run(4)) {
+        (Ok(a), Ok(b), Ok(c)) if a == b && b == c => Ok(format!("(hash {:016x})", a)),
+        (Ok(a), Ok(b), Ok(c)) => Err(format!("hashes {:016x}, {:016x} and {:016x} differ", a, b, c)),
+        (Err(err), _, _) | (_, Err(err), _) | (_, _, Err(err)) => Err(err.to_string()),
+    };
+    Check { name: "reproducibility".to_string(), outcome }
+}
+
+// Tour evaluations per second on a 200-city instance. Always passes; the
+// figure is for comparing machines and builds.
+fn throughput() -> Check {
+    let problem = TspProblem::new(instances::ring(200, 10.0));
+    let mut rng = SmallRng::seed_from_u64(SEED);
+    let tours: Vec<Vec<usize>> = (0..100).map(|_| random_order(200, &mut rng)).collect();
+
+    let started = Instant::now();
+    let mut evaluations = 0;
+    while started.elapsed().as_millis() < 200 {
+        for tour in &tours {
+            if Path::from_order(tour.clone(), &problem).is_err() {
+                return Check { name: "throughput".to_string(), outcome: Err("invalid tour".to_string()) };
+            }
+            evaluations += 1;
+        }
+    }
+
+    let rate = evaluations as f64 / started.elapsed().as_secs_f64();
+    Check { name: "throughput".to_string(), outcome: Ok(format!("({:.0} evaluations/s)", rate)) }
+}
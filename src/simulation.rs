@@ -0,0 +1,1621 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{self, File};
+use std::ops::Range;
+use std::io::{BufWriter, Write};
+use std::mem;
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rand::{FromEntropy, Rng, SeedableRng};
+use rand::rngs::SmallRng;
+
+use crate::archive::Archive;
+use crate::checkpoint::Checkpoint;
+use crate::chromosome::{rank, Chromosome, Distance, Fnv};
+use crate::city::City;
+use crate::cost::{CostMode, Objective, TourType};
+use crate::error::{check_rate, Error};
+use crate::crossover::CrossoverOperator;
+use crate::events::{Event, Subscriber, Subscribers, TopKSink};
+use crate::export::{ExportFormat, Results};
+use crate::metric::DistanceMetric;
+use crate::mutation::MutationOperator;
+use crate::matrix::DistanceMatrix;
+use crate::parallel::{breed_parallel, breed_serial, Breeder, Job};
+use crate::path::{Path, TspProblem};
+use crate::render::path_svg;
+use crate::route::Route;
+use crate::selection::{partition_fittest, Mating, Pool, Replacement, Selection, MAX_REDRAWS};
+use crate::stats::StatsRecorder;
+
+#[derive(Clone, Debug)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub best_fitness: f64,
+    pub best_cost: f64,
+    pub mean_fitness: f64,
+    // Population standard deviation of fitness.
+    pub std_dev: f64,
+    pub worst_fitness: f64,
+    // Share of the population with a distinct gene fingerprint, from 1/n
+    // (all identical) to 1.0 (all different).
+    pub diversity: f64,
+    pub population_hash: u64,
+    // Relative distance of best_cost above the lower bound, when one is set.
+    pub gap: Option<f64>,
+    // The seed this generation was bred from, when generation seeds are on.
+    pub seed: Option<u64>,
+    // The mutation rate the next generation is bred with.
+    pub mutation_rate: f64,
+}
+
+impl fmt::Display for GenerationStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Generation: {}, Best fitness: {}, Best cost: {}, Mean fitness: {}, Std dev: {}, Worst fitness: {}, Diversity: {:.3}, Hash: {:016x}",
+            self.generation,
+            self.best_fitness,
+            self.best_cost,
+            self.mean_fitness,
+            self.std_dev,
+            self.worst_fitness,
+            self.diversity,
+            self.population_hash
+        )?;
+        if let Some(gap) = self.gap {
+            write!(f, ", Gap: {:.2}%", gap * 100.0)?;
+        }
+        if let Some(seed) = self.seed {
+            write!(f, ", Seed: {}", seed)?;
+        }
+        Ok(())
+    }
+}
+
+// Outcome of Simulation::run: the fittest solution seen, its fitness and
+// cost (route length for a tour), and the generation it was found in.
+#[derive(Clone, Debug)]
+pub struct SimulationResult<C> {
+    pub best: C,
+    pub fitness: f64,
+    pub cost: f64,
+    pub generation: usize,
+}
+
+// What a tour run is expected to cost, from Simulation::estimate. Memory
+// counts the population, the offspring bred alongside it and the distance
+// matrix; run_time is time_per_generation over every remaining generation.
+#[derive(Clone, Debug)]
+pub struct Estimate {
+    pub evaluations_per_generation: usize,
+    pub memory_bytes: usize,
+    pub time_per_generation: Duration,
+    pub run_time: Duration,
+}
+
+// How much the best cost fell in each phase of a run. Initialization is the
+// gap between the initial population's mean and best cost; every later drop
+// in the best cost is credited to the step that produced the new best: the
+// crossover that made it, the mutation applied afterwards, the local search
+// that polished it, or an immigrant.
+#[derive(Clone, Debug, Default)]
+pub struct PhaseReport {
+    pub initialization: f64,
+    pub crossover: f64,
+    pub mutation: f64,
+    pub local_search: f64,
+    pub immigration: f64,
+}
+
+impl PhaseReport {
+    pub fn total(&self) -> f64 {
+        self.initialization + self.crossover + self.mutation + self.local_search + self.immigration
+    }
+}
+
+impl fmt::Display for PhaseReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let total = self.total();
+        let share = |gain: f64| if total > 0.0 { gain / total * 100.0 } else { 0.0 };
+        let phases = [
+            ("initialization", self.initialization),
+            ("crossover", self.crossover),
+            ("mutation", self.mutation),
+            ("local search", self.local_search),
+            ("immigration", self.immigration),
+        ];
+
+        for (i, (name, gain)) in phases.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}: {} ({:.1}%)", name, gain, share(*gain))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct Simulation<C: Chromosome = Path, R = SmallRng> {
+    population: Vec<C>,
+    problem: C::Problem,
+    max_iterations: usize,
+    crossover_rate: f64,
+    mutation_rate: f64,
+    survival_rate: f64,
+    elitism_count: usize,
+    rng: R,
+    fittest: C,
+    generation: usize,
+    archive: Option<Archive<C>>,
+    subscribers: Subscribers,
+    last_improvement: usize,
+    stagnation_threshold: Option<usize>,
+    immigration: Option<Immigration<C>>,
+    threads: usize,
+    breeder: Breeder<C>,
+    top_k_log: Option<TopKLog<C>>,
+    time_budget: Option<TimeBudget>,
+    lower_bound: Option<f64>,
+    phases: PhaseReport,
+    bred_best: Option<Origin>,
+    recorder: Option<StatsRecorder>,
+    local_search: Option<LocalSearch<C>>,
+    generation_seeds: Option<GenerationSeeds<R>>,
+    adaptive_mutation: Option<AdaptiveMutation>,
+    snapshots: Option<Snapshots<C>>,
+    selection: Selection,
+    oversampling: Option<f64>,
+    mating: Option<MatingPolicy<C>>,
+    replacement: Replacement,
+}
+
+// Where the fittest member of the latest bred generation came from.
+#[derive(Clone, Copy)]
+struct Origin {
+    fitness: f64,
+    unmutated_fitness: f64,
+    crossover: bool,
+    mutated: bool,
+    // Fitness before local search, when it was applied.
+    searched_from: Option<f64>,
+}
+
+#[derive(Clone)]
+struct TopKLog<C> {
+    k: usize,
+    every: usize,
+    sink: TopKSink,
+    describe: fn(&C) -> String,
+}
+
+// Improves the fittest `rate` share of each generation's offspring with
+// `improve`; suspended while the time budget is overrun.
+#[derive(Clone)]
+struct LocalSearch<C: Chromosome> {
+    rate: f64,
+    improve: fn(&mut C, &C::Problem) -> bool,
+    suspended: bool,
+}
+
+// Reseeds the RNG from `master` before every generation; `seeded` is
+// R::seed_from_u64, kept so stepping needs no SeedableRng bound.
+#[derive(Clone)]
+struct GenerationSeeds<R> {
+    master: u64,
+    seeded: fn(u64) -> R,
+}
+
+// The mating policy, with the genomes' Distance kept as a function pointer
+// so breeding needs no Distance bound.
+#[derive(Clone)]
+struct MatingPolicy<C> {
+    mating: Mating,
+    distance: fn(&C, &C) -> f64,
+}
+
+// Bounds and diversity target of the adaptive mutation rate.
+#[derive(Clone, Copy)]
+struct AdaptiveMutation {
+    min_rate: f64,
+    max_rate: f64,
+    target_diversity: f64,
+}
+
+// Every `every` generations, the fittest individual as drawn by `render` is
+// written to `dir`.
+#[derive(Clone)]
+struct Snapshots<C: Chromosome> {
+    dir: PathBuf,
+    every: usize,
+    render: fn(&C, &C::Problem) -> String,
+}
+
+// How each generation of `size` members is made up: the `elites`, then the
+// other retained parents up to `retained`, all copied unchanged, then
+// `offspring` bred from the fittest `breeders` (the breeding pool under
+// truncation). The rates' shares are rounded down, but at least one member
+// breeds and at least one slot is bred, so any population of two or more
+// has a valid composition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Composition {
+    breeders: usize,
+    elites: usize,
+    retained: usize,
+    offspring: usize,
+}
+
+impl Composition {
+    fn new(size: usize, crossover_rate: f64, survival_rate: f64, elites: usize) -> Composition {
+        let breeders = share(size, crossover_rate).clamp(1, size);
+        let retained = share(breeders, survival_rate).max(elites).min(size - 1);
+        Composition { breeders, elites, retained, offspring: size - retained }
+    }
+}
+
+// `rate` of `count`, rounded down, ignoring floating-point error so that
+// e.g. 29% of 100 is 29 rather than 28.
+fn share(count: usize, rate: f64) -> usize {
+    (count as f64 * rate + 1e-9) as usize
+}
+
+#[derive(Clone)]
+struct TimeBudget {
+    budget: Duration,
+    offspring: usize,
+}
+
+#[derive(Clone)]
+struct Immigration<C> {
+    immigrants: Vec<C>,
+    every: usize,
+    count: usize,
+    next: usize,
+}
+
+// Simulations are moved onto worker threads and cloned for what-if runs.
+const _: fn() = || {
+    fn assert_bounds<T: Send + Sync + Clone>() {}
+    assert_bounds::<City>();
+    assert_bounds::<Path>();
+    assert_bounds::<Simulation>();
+};
+
+impl Simulation {
+    pub fn new(
+        initial_population: Vec<Path>,
+        city_list: Vec<City>,
+        max_iterations: usize,
+        crossover_rate: f64,
+        mutation_rate: f64,
+        survival_rate: f64,
+    ) -> Result<Simulation, Error> {
+        Simulation::with_rng(
+            initial_population,
+            city_list,
+            max_iterations,
+            crossover_rate,
+            mutation_rate,
+            survival_rate,
+            SmallRng::from_entropy(),
+        )
+    }
+}
+
+impl<R: Rng> Simulation<Path, R> {
+    pub fn with_rng(
+        initial_population: Vec<Path>,
+        city_list: Vec<City>,
+        max_iterations: usize,
+        crossover_rate: f64,
+        mutation_rate: f64,
+        survival_rate: f64,
+        rng: R,
+    ) -> Result<Simulation<Path, R>, Error> {
+        if city_list.is_empty() {
+            return Err(Error::EmptyCityList);
+        }
+        if let Some(p) = initial_population.iter().find(|p| p.order.len() != city_list.len()) {
+            return Err(Error::InvalidTour(format!(
+                "path visits {} cities but the city list has {}",
+                p.order.len(),
+                city_list.len()
+            )));
+        }
+
+        Simulation::with_problem(
+            initial_population,
+            TspProblem::new(city_list),
+            max_iterations,
+            crossover_rate,
+            mutation_rate,
+            survival_rate,
+            rng,
+        )
+    }
+
+    // Switches how tour costs are accumulated and re-evaluates the current
+    // population under the new mode.
+    pub fn with_cost_mode(mut self, cost_mode: CostMode) -> Simulation<Path, R> {
+        self.problem.cost_model.mode = cost_mode;
+        self.reevaluate();
+        self
+    }
+
+    pub fn with_crossover<O>(mut self, operator: O) -> Simulation<Path, R>
+    where
+        O: CrossoverOperator + 'static,
+    {
+        self.problem.crossover = Arc::new(operator);
+        self
+    }
+
+    pub fn with_mutation<O>(mut self, operator: O) -> Simulation<Path, R>
+    where
+        O: MutationOperator + 'static,
+    {
+        self.problem.mutation = Arc::new(operator);
+        self
+    }
+
+    // Computes a lower bound on the tour length once, the 1-tree bound for
+    // closed tours or the minimum spanning tree weight for open ones, and
+    // reports each generation's gap to it in GenerationStats. No tour is
+    // shorter than the bound, so the gap caps how far the best tour can be
+    // from optimal. The bound is computed over the distance matrix and only
+    // holds for float distance costs over mandatory cities, so call this
+    // after choosing the cost model; in any other setup no gap is reported.
+    pub fn with_mst_bound(mut self) -> Simulation<Path, R> {
+        let model = self.problem.cost_model;
+        let cities = &self.problem.city_list;
+        let applies = model.mode == CostMode::Float
+            && model.objective == Objective::Distance
+            && cities.iter().all(|c| c.is_mandatory());
+
+        let matrix = &self.problem.matrix;
+        self.lower_bound = match model.tour_type {
+            _ if !applies => None,
+            TourType::Closed => Some(matrix.one_tree_bound()),
+            TourType::Open => Some(matrix.mst_weight()),
+        };
+        self
+    }
+
+    // Measures edges with `metric`, e.g. Manhattan or Haversine, instead of
+    // Euclidean distance. The distance matrix is rebuilt, the population
+    // re-evaluated and any lower bound recomputed. Checkpoints do not store
+    // the metric; set it again after resume.
+    pub fn with_metric(mut self, metric: &dyn DistanceMetric) -> Simulation<Path, R> {
+        self.problem.matrix = Arc::new(DistanceMatrix::with_metric(&self.problem.city_list, metric));
+        self.reevaluate();
+        if self.lower_bound.is_some() {
+            self = self.with_mst_bound();
+        }
+        self
+    }
+
+    // Chooses whether tours return to their first city, re-evaluating the
+    // current population.
+    pub fn with_tour_type(mut self, tour_type: TourType) -> Simulation<Path, R> {
+        self.problem.cost_model.tour_type = tour_type;
+        self.reevaluate();
+        self
+    }
+
+    // Optimises `objective` instead of plain distance, re-evaluating the
+    // current population.
+    pub fn with_objective(mut self, objective: Objective) -> Simulation<Path, R> {
+        self.problem.cost_model.objective = objective;
+        self.reevaluate();
+        self
+    }
+
+    // The best tour found so far, with city names where the cities have them.
+    pub fn route(&self) -> Route {
+        Route::of(&self.fittest, &self.problem)
+    }
+
+    // Writes the best tour (its stops with their coordinates and its
+    // length) and the fitness history to `writer`. The history is the stats
+    // recorder's when with_stats_recorder is on, otherwise just the current
+    // generation.
+    pub fn export_results<W: Write>(&self, writer: W, format: ExportFormat) -> Result<(), Error> {
+        let current = [self.stats()];
+        let history = self.recorder.as_ref().map_or(&current[..], |recorder| recorder.history());
+
+        Results {
+            length: self.fittest.cost(),
+            tour: self.fittest.visited(&self.problem),
+            cities: &self.problem.city_list,
+            history,
+        }.write(writer, format)
+    }
+
+    // Writes the best tour as an SVG image, `generation-NNNNNN.svg`, to `dir`
+    // now and every `every` generations, so a run can be turned into an
+    // animation. Writes are best-effort; a failing write does not stop the
+    // run.
+    pub fn with_svg_snapshots(mut self, dir: &FsPath, every: usize) -> Result<Simulation<Path, R>, Error> {
+        fs::create_dir_all(dir)?;
+        self.snapshots = if every > 0 {
+            Some(Snapshots { dir: dir.to_path_buf(), every, render: path_svg })
+        } else {
+            None
+        };
+        self.save_snapshot();
+        Ok(self)
+    }
+
+    // Turns the GA into a memetic algorithm: each generation, the fittest
+    // `rate` share of the offspring (at least one when the rate is not zero)
+    // is improved with 2-opt and Or-opt moves before selection.
+    pub fn with_local_search(mut self, rate: f64) -> Result<Simulation<Path, R>, Error> {
+        check_rate("local_search_rate", rate)?;
+        self.local_search = if rate > 0.0 {
+            Some(LocalSearch { rate, improve: Path::improve, suspended: false })
+        } else {
+            None
+        };
+        Ok(self)
+    }
+}
+
+impl<C, R> Simulation<C, R>
+where
+    C: Chromosome + Send + Sync,
+    C::Problem: Sync,
+    R: Rng,
+{
+    // Breeds, mutates and evaluates each generation on `threads` worker
+    // threads. Every offspring draws from its own pre-seeded RNG, so results
+    // do not depend on the thread count.
+    pub fn with_threads(mut self, threads: usize) -> Simulation<C, R> {
+        self.threads = threads.max(1);
+        self.breeder = if self.threads > 1 { breed_parallel::<C> } else { breed_serial::<C> };
+        self
+    }
+}
+
+impl<C: Chromosome + fmt::Display, R: Rng> Simulation<C, R> {
+    // Records the `k` fittest individuals every `every` generations to `sink`.
+    pub fn with_top_k_log(mut self, k: usize, every: usize, sink: TopKSink) -> Simulation<C, R> {
+        self.top_k_log = if every > 0 && k > 0 {
+            Some(TopKLog { k, every, sink, describe: |c: &C| c.to_string() })
+        } else {
+            None
+        };
+        self
+    }
+}
+
+impl<C: Chromosome, R: Rng + SeedableRng> Simulation<C, R> {
+    // Reseeds the simulation's RNG. Together with an initial population built
+    // from a seeded RNG this makes a run fully reproducible.
+    pub fn with_seed(mut self, seed: u64) -> Simulation<C, R> {
+        self.rng = R::seed_from_u64(seed);
+        self
+    }
+
+    // Breeds every generation from its own seed, generation_seed(master, g)
+    // for generation g, instead of one RNG stream for the whole run. Each
+    // generation's seed is reported in GenerationStats, so a single
+    // generation can be re-executed in isolation: resume the checkpoint
+    // saved before it (or rebuild its population) and step once.
+    pub fn with_generation_seeds(mut self, master: u64) -> Simulation<C, R> {
+        self.generation_seeds = Some(GenerationSeeds { master, seeded: R::seed_from_u64 });
+        self
+    }
+}
+
+impl<R: Rng + SeedableRng> Simulation<Path, R> {
+    // Writes the run's state (cities, cost model, rates, generation counters,
+    // population, best tour, RNG state and generation seeds) to `file`. The RNG is reseeded
+    // from itself and the new seed is saved, so a run resumed from the file
+    // continues exactly as this one does. Operators, subscribers and
+    // optional features such as the archive or immigration are not saved;
+    // configure them again after resume.
+    pub fn save_checkpoint(&mut self, file: &FsPath) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(file)?);
+        self.write_checkpoint(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    // The checkpoint save_checkpoint writes, to any writer rather than a
+    // file, for targets without a filesystem: a browser build can keep it
+    // in IndexedDB or localStorage and pass it back to resume_from_str. A
+    // Vec<u8> holds exactly the text, a few bytes per city and per gene.
+    pub fn write_checkpoint<W: Write>(&mut self, writer: W) -> Result<(), Error> {
+        let seed: u64 = self.rng.gen();
+        self.rng = R::seed_from_u64(seed);
+
+        let checkpoint = Checkpoint {
+            generation: self.generation,
+            last_improvement: self.last_improvement,
+            max_iterations: self.max_iterations,
+            crossover_rate: self.crossover_rate,
+            mutation_rate: self.mutation_rate,
+            survival_rate: self.survival_rate,
+            elitism_count: self.elitism_count,
+            cost_model: self.problem.cost_model,
+            seed,
+            generation_seeds: self.generation_seeds.as_ref().map(|seeds| seeds.master),
+            cities: self.problem.city_list.clone(),
+            population: self.population.iter().map(|p| p.order.clone()).collect(),
+            fittest: self.fittest.order.clone(),
+        };
+
+        checkpoint.write(writer)
+    }
+
+    // Sizes the run without running it: the composition gives the
+    // evaluations per generation and the memory, and `samples` generations
+    // of a copy with no subscribers or snapshots give the time per
+    // generation. Later generations can breed at a different pace, so the
+    // time is rough; the run itself is left untouched.
+    pub fn estimate(&self, samples: usize) -> Estimate
+    where
+        R: Clone,
+    {
+        let size = self.population.len();
+        let evaluations = match self.replacement {
+            Replacement::SteadyState { offspring } => offspring.min(size - self.elitism_count),
+            Replacement::Generational => {
+                let offspring = self.offspring_slots();
+                self.oversampling.map_or(offspring, |f| (offspring as f64 * f).ceil() as usize)
+            }
+        };
+        let cities = self.problem.city_list.len();
+        let path = mem::size_of::<Path>() + cities * mem::size_of::<usize>();
+        let memory_bytes = (size + evaluations) * path + cities * cities * mem::size_of::<f64>();
+
+        let mut trial = self.clone();
+        trial.subscribers.clear();
+        trial.snapshots = None;
+        trial.top_k_log = None;
+        let samples = samples.max(1);
+        let started = Instant::now();
+        for _ in 0..samples {
+            trial.step();
+        }
+        let time_per_generation = started.elapsed() / samples as u32;
+        let remaining = self.max_iterations.saturating_sub(self.generation);
+
+        Estimate {
+            evaluations_per_generation: evaluations,
+            memory_bytes,
+            time_per_generation,
+            run_time: time_per_generation * remaining as u32,
+        }
+    }
+
+    // Rebuilds a simulation from a file written by save_checkpoint.
+    pub fn resume(file: &FsPath) -> Result<Simulation<Path, R>, Error> {
+        Simulation::resume_from_str(&fs::read_to_string(file)?)
+    }
+
+    // Rebuilds a simulation from checkpoint text written by write_checkpoint.
+    pub fn resume_from_str(text: &str) -> Result<Simulation<Path, R>, Error> {
+        let checkpoint = Checkpoint::parse(text)?;
+
+        let mut problem = TspProblem::new(checkpoint.cities);
+        problem.cost_model = checkpoint.cost_model;
+        let population = checkpoint.population.into_iter()
+            .map(|order| Path::from_order(order, &problem))
+            .collect::<Result<Vec<Path>, Error>>()?;
+        let fittest = Path::from_order(checkpoint.fittest, &problem)?;
+
+        let mut sim = Simulation::with_problem(
+            population,
+            problem,
+            checkpoint.max_iterations,
+            checkpoint.crossover_rate,
+            checkpoint.mutation_rate,
+            checkpoint.survival_rate,
+            R::seed_from_u64(checkpoint.seed),
+        )?.with_elitism(checkpoint.elitism_count)?;
+
+        sim.fittest = fittest;
+        sim.generation = checkpoint.generation;
+        sim.last_improvement = checkpoint.last_improvement;
+        if let Some(master) = checkpoint.generation_seeds {
+            sim = sim.with_generation_seeds(master);
+        }
+        Ok(sim)
+    }
+}
+
+impl<C: Chromosome + Distance, R: Rng> Simulation<C, R> {
+    // Keeps an archive of near-best but structurally distinct solutions
+    // alongside the single fittest one; see Archive for the admission rules.
+    pub fn with_archive(mut self, epsilon: f64, min_distance: f64, capacity: usize) -> Simulation<C, R> {
+        let mut archive = Archive::new(epsilon, min_distance, capacity);
+        archive.update(&self.fittest, &self.population);
+        self.archive = Some(archive);
+        self
+    }
+
+    // Chooses how each mother's partner is picked; see Mating.
+    pub fn with_mating(mut self, mating: Mating) -> Result<Simulation<C, R>, Error> {
+        self.mating = match mating {
+            Mating::Random => None,
+            Mating::Dissimilar { candidates: 0 } => {
+                return Err(Error::InvalidParameter { name: "mating candidates", value: 0.0 });
+            }
+            _ => Some(MatingPolicy { mating, distance: C::distance }),
+        };
+        Ok(self)
+    }
+}
+
+impl<C: Chromosome, R: Rng> Simulation<C, R> {
+    pub fn with_problem(
+        initial_population: Vec<C>,
+        problem: C::Problem,
+        max_iterations: usize,
+        crossover_rate: f64,
+        mutation_rate: f64,
+        survival_rate: f64,
+        rng: R,
+    ) -> Result<Simulation<C, R>, Error> {
+        check_rate("crossover_rate", crossover_rate)?;
+        check_rate("mutation_rate", mutation_rate)?;
+        check_rate("survival_rate", survival_rate)?;
+
+        // Breeding needs two members; see Composition for how they are split.
+        let size = initial_population.len();
+        if size == 0 {
+            return Err(Error::EmptyPopulation);
+        }
+        if size < 2 {
+            return Err(Error::PopulationTooSmall { size });
+        }
+
+        let fittest = initial_population[0].clone();
+        let mut sim = Simulation {
+            population: initial_population,
+            problem,
+            max_iterations,
+            crossover_rate,
+            mutation_rate,
+            survival_rate,
+            elitism_count: 0,
+            rng,
+            fittest,
+            generation: 0,
+            archive: None,
+            subscribers: Subscribers::default(),
+            last_improvement: 0,
+            stagnation_threshold: None,
+            immigration: None,
+            threads: 1,
+            breeder: breed_serial::<C>,
+            top_k_log: None,
+            time_budget: None,
+            lower_bound: None,
+            phases: PhaseReport::default(),
+            bred_best: None,
+            recorder: None,
+            local_search: None,
+            generation_seeds: None,
+            adaptive_mutation: None,
+            snapshots: None,
+            selection: Selection::default(),
+            oversampling: None,
+            mating: None,
+            replacement: Replacement::default(),
+        };
+        sim.fittest = sim.find_fittest();
+        sim.phases.initialization = sim.initialization_gain();
+        Ok(sim)
+    }
+
+    // Carries the `count` fittest individuals into every next generation
+    // unchanged, never mutated. They count towards the surviving parents, so
+    // the survivors are the larger of `count` and the survival rate's share.
+    pub fn with_elitism(mut self, count: usize) -> Result<Simulation<C, R>, Error> {
+        if count >= self.population.len() {
+            return Err(Error::InvalidParameter { name: "elitism_count", value: count as f64 });
+        }
+
+        self.elitism_count = count;
+        Ok(self)
+    }
+
+    // Registers a closure that receives every lifecycle Event.
+    pub fn subscribe<F>(&mut self, subscriber: F)
+    where
+        F: FnMut(&Event) + Send + 'static,
+    {
+        self.subscribe_shared(Arc::new(Mutex::new(subscriber)));
+    }
+
+    pub fn subscribe_shared(&mut self, subscriber: Subscriber) {
+        self.subscribers.push(subscriber);
+    }
+
+    // Publishes Event::Stagnation once the best fitness has gone `generations`
+    // generations without improving.
+    pub fn with_stagnation_threshold(mut self, generations: usize) -> Simulation<C, R> {
+        self.stagnation_threshold = Some(generations);
+        self
+    }
+
+    // Chooses how parents are selected; see Selection.
+    pub fn with_selection(mut self, selection: Selection) -> Result<Simulation<C, R>, Error> {
+        if let Selection::Tournament { size: 0 } = selection {
+            return Err(Error::InvalidParameter { name: "tournament size", value: 0.0 });
+        }
+        self.selection = selection;
+        Ok(self)
+    }
+
+    // Chooses between rebuilding the population each step and steady-state
+    // replacement of its weakest members; see Replacement.
+    pub fn with_replacement(mut self, replacement: Replacement) -> Result<Simulation<C, R>, Error> {
+        if let Replacement::SteadyState { offspring } = replacement {
+            if offspring == 0 || offspring >= self.population.len() {
+                return Err(Error::InvalidParameter { name: "steady-state offspring", value: offspring as f64 });
+            }
+        }
+        self.replacement = replacement;
+        Ok(self)
+    }
+
+    // Breeds `factor` times as many offspring as there are offspring slots
+    // and keeps only the fittest of them, a (mu, lambda) strategy: each
+    // generation costs more evaluations but its offspring are better, and
+    // with_threads spreads the extra breeding over the worker threads.
+    // A factor of 1 turns oversampling off.
+    pub fn with_oversampling(mut self, factor: f64) -> Result<Simulation<C, R>, Error> {
+        if !(factor >= 1.0 && factor.is_finite()) {
+            return Err(Error::InvalidParameter { name: "oversampling factor", value: factor });
+        }
+        self.oversampling = if factor > 1.0 { Some(factor) } else { None };
+        Ok(self)
+    }
+
+    // Adapts the mutation rate to the population's diversity after every
+    // generation: at or above `target_diversity` the rate falls to
+    // `min_rate`, and below it the rate rises linearly towards `max_rate`,
+    // reached when every individual is identical. Converging populations are
+    // shaken up while diverse ones are left to exploit their good tours.
+    pub fn with_adaptive_mutation(
+        mut self,
+        min_rate: f64,
+        max_rate: f64,
+        target_diversity: f64,
+    ) -> Result<Simulation<C, R>, Error> {
+        check_rate("min_rate", min_rate)?;
+        check_rate("max_rate", max_rate)?;
+        check_rate("target_diversity", target_diversity)?;
+        if min_rate > max_rate {
+            return Err(Error::InvalidParameter { name: "min_rate", value: min_rate });
+        }
+
+        self.adaptive_mutation = Some(AdaptiveMutation { min_rate, max_rate, target_diversity });
+        self.adapt_mutation_rate(self.stats().diversity);
+        Ok(self)
+    }
+
+    // Every `every` generations, replaces the `count` weakest individuals with
+    // the next members of `immigrants`, cycling through them. Typically the
+    // immigrants are hall-of-fame solutions from earlier runs.
+    pub fn with_immigrants(mut self, mut immigrants: Vec<C>, every: usize, count: usize) -> Simulation<C, R> {
+        for immigrant in immigrants.iter_mut() {
+            immigrant.evaluate(&self.problem);
+        }
+        if !immigrants.is_empty() && every > 0 {
+            self.immigration = Some(Immigration { immigrants, every, count, next: 0 });
+        }
+        self
+    }
+
+    // Keeps each generation's breeding within `budget` of wall-clock time.
+    // When a generation overruns, the number of offspring bred is cut in
+    // proportion to the overrun and the freed slots are filled with unbred
+    // copies of the next-ranked individuals; once generations finish in under
+    // half the budget the count grows back. Each change is reported as
+    // Event::OffspringAdjusted. Local search, when enabled, is the first
+    // thing given up on an overrun and the last restored, reported as
+    // Event::LocalSearchToggled.
+    pub fn with_generation_budget(mut self, budget: Duration) -> Simulation<C, R> {
+        let offspring = self.offspring_slots();
+        self.time_budget = Some(TimeBudget { budget, offspring });
+        self
+    }
+
+    // Keeps every generation's stats, starting with the current one, for
+    // analysis after the run; see stats_recorder.
+    pub fn with_stats_recorder(mut self) -> Simulation<C, R> {
+        let mut recorder = StatsRecorder::default();
+        recorder.record(&self.stats());
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub fn stats_recorder(&self) -> Option<&StatsRecorder> {
+        self.recorder.as_ref()
+    }
+
+    pub fn archive(&self) -> &[C] {
+        self.archive.as_ref().map_or(&[], |a| a.members())
+    }
+
+    // Costs change meaning under a new evaluation, so the phase report starts
+    // over from the re-evaluated population.
+    fn reevaluate(&mut self) {
+        for p in self.population.iter_mut() {
+            p.evaluate(&self.problem);
+        }
+        self.fittest = self.find_fittest();
+        self.phases = PhaseReport { initialization: self.initialization_gain(), ..PhaseReport::default() };
+
+        if let Some(archive) = self.archive.as_mut() {
+            archive.clear();
+            archive.update(&self.fittest, &self.population);
+        }
+    }
+
+    pub fn mutation_rate(&self) -> f64 {
+        self.mutation_rate
+    }
+
+    pub fn fittest(&self) -> &C {
+        &self.fittest
+    }
+
+    pub fn problem(&self) -> &C::Problem {
+        &self.problem
+    }
+
+    pub fn phase_report(&self) -> &PhaseReport {
+        &self.phases
+    }
+
+    pub fn run(&mut self) -> SimulationResult<C> {
+        self.run_until(|_| false);
+        self.result()
+    }
+
+    pub(crate) fn result(&self) -> SimulationResult<C> {
+        SimulationResult {
+            best: self.fittest.clone(),
+            fitness: self.fittest.fitness(),
+            cost: 1.0 / self.fittest.fitness(),
+            generation: self.last_improvement,
+        }
+    }
+
+    // Like run, but hands every generation's stats to `observer` as it
+    // completes, for progress bars, GUIs and loggers.
+    pub fn run_with<F>(&mut self, mut observer: F) -> SimulationResult<C>
+    where
+        F: FnMut(&GenerationStats),
+    {
+        self.run_until(|stats| {
+            observer(stats);
+            false
+        });
+        self.result()
+    }
+
+    // Runs until `done` accepts a generation's stats or max_iterations is hit,
+    // returning the stats of the last generation evaluated.
+    pub fn run_until<F>(&mut self, mut done: F) -> GenerationStats
+    where
+        F: FnMut(&GenerationStats) -> bool,
+    {
+        let max_iterations = self.max_iterations;
+        let mut last = self.stats();
+        self.subscribers.publish(Event::RunStarted { generation: self.generation });
+
+        for stats in self.iter().take(max_iterations) {
+            let finished = done(&stats);
+            last = stats;
+            if finished {
+                break;
+            }
+        }
+
+        self.subscribers.publish(Event::RunFinished(last.clone()));
+        last
+    }
+
+    // Steps the simulation lazily, one generation per item. The iterator is
+    // unbounded; callers decide when to stop.
+    pub fn iter(&mut self) -> Generations<'_, C, R> {
+        Generations { simulation: self }
+    }
+
+    // Advances `k` generations and returns their stats, oldest first, for
+    // hosts that run the solver in slices between other work, such as a
+    // browser worker posting progress to the page after every call.
+    pub fn step_n(&mut self, k: usize) -> Vec<GenerationStats> {
+        self.iter().take(k).collect()
+    }
+
+    pub(crate) fn step(&mut self) -> GenerationStats {
+        if let Some(seeds) = self.generation_seeds.as_ref() {
+            self.rng = (seeds.seeded)(generation_seed(seeds.master, self.generation + 1));
+        }
+
+        let started = Instant::now();
+        self.generate_next_generation();
+        self.generation += 1;
+        self.adapt_to_budget(started.elapsed());
+        self.immigrate();
+
+        let challenger = self.find_fittest();
+        if rank(&challenger, &self.fittest) == Ordering::Less {
+            self.credit_improvement(&challenger);
+            self.fittest = challenger;
+            self.last_improvement = self.generation;
+            self.subscribers.publish(Event::NewBestFound {
+                generation: self.generation,
+                best_fitness: self.fittest.fitness(),
+            });
+        }
+
+        if let Some(archive) = self.archive.as_mut() {
+            archive.update(&self.fittest, &self.population);
+        }
+
+        let stalled = self.generation - self.last_improvement;
+        if self.stagnation_threshold == Some(stalled) && stalled > 0 {
+            self.subscribers.publish(Event::Stagnation {
+                generation: self.generation,
+                generations_without_improvement: stalled,
+            });
+        }
+
+        self.log_top_k();
+        self.save_snapshot();
+
+        let mut stats = self.stats();
+        self.adapt_mutation_rate(stats.diversity);
+        stats.mutation_rate = self.mutation_rate;
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.record(&stats);
+        }
+        self.subscribers.publish(Event::GenerationCompleted(stats.clone()));
+        stats
+    }
+
+    fn initialization_gain(&self) -> f64 {
+        let mean = self.population.iter().map(|p| 1.0 / p.fitness()).sum::<f64>() / self.population.len() as f64;
+        mean - 1.0 / self.fittest.fitness()
+    }
+
+    // Splits the drop from the current best to `challenger` between crossover,
+    // mutation and local search when the challenger was bred this generation, or credits
+    // it to immigration when it arrived as an immigrant.
+    fn credit_improvement(&mut self, challenger: &C) {
+        let previous = 1.0 / self.fittest.fitness();
+        let gain = previous - 1.0 / challenger.fitness();
+
+        match self.bred_best {
+            Some(origin) if origin.fitness == challenger.fitness() => {
+                let from_search = origin.searched_from
+                    .map_or(0.0, |before| (1.0 / before - 1.0 / origin.fitness).clamp(0.0, gain));
+                let bred = gain - from_search;
+                let from_crossover = if !origin.crossover {
+                    0.0
+                } else if origin.mutated {
+                    (previous - 1.0 / origin.unmutated_fitness).clamp(0.0, bred)
+                } else {
+                    bred
+                };
+                self.phases.crossover += from_crossover;
+                self.phases.mutation += bred - from_crossover;
+                self.phases.local_search += from_search;
+            }
+            _ => self.phases.immigration += gain,
+        }
+    }
+
+    fn adapt_mutation_rate(&mut self, diversity: f64) {
+        if let Some(adaptive) = self.adaptive_mutation {
+            let shortfall = if adaptive.target_diversity > 0.0 {
+                (1.0 - diversity / adaptive.target_diversity).max(0.0)
+            } else {
+                0.0
+            };
+            self.mutation_rate = adaptive.min_rate + (adaptive.max_rate - adaptive.min_rate) * shortfall;
+        }
+    }
+
+    fn adapt_to_budget(&mut self, elapsed: Duration) {
+        let full = self.offspring_slots();
+        let budget = match self.time_budget.as_mut() {
+            Some(budget) => budget,
+            None => return,
+        };
+
+        let current = budget.offspring.min(full);
+        let toggle = match self.local_search.as_ref() {
+            Some(search) if !search.suspended => elapsed > budget.budget,
+            Some(_) => elapsed * 2 < budget.budget && current == full,
+            None => false,
+        };
+        if let Some(search) = self.local_search.as_mut().filter(|_| toggle) {
+            search.suspended = !search.suspended;
+            self.subscribers.publish(Event::LocalSearchToggled {
+                generation: self.generation,
+                elapsed,
+                enabled: !search.suspended,
+            });
+            return;
+        }
+
+        let next = if elapsed > budget.budget {
+            let scale = budget.budget.as_secs_f64() / elapsed.as_secs_f64();
+            ((current as f64 * scale) as usize).max(1)
+        } else if elapsed * 2 < budget.budget {
+            (current + current / 4 + 1).min(full)
+        } else {
+            current
+        };
+
+        if next != current {
+            budget.offspring = next;
+            self.subscribers.publish(Event::OffspringAdjusted {
+                generation: self.generation,
+                elapsed,
+                offspring: next,
+            });
+        }
+    }
+
+    fn save_snapshot(&self) {
+        if let Some(snapshots) = self.snapshots.as_ref().filter(|s| self.generation.is_multiple_of(s.every)) {
+            let file = snapshots.dir.join(format!("generation-{:06}.svg", self.generation));
+            let _ = fs::write(file, (snapshots.render)(&self.fittest, &self.problem));
+        }
+    }
+
+    fn log_top_k(&self) {
+        let log = match self.top_k_log.as_ref() {
+            Some(log) if self.generation.is_multiple_of(log.every) => log,
+            _ => return,
+        };
+
+        let mut ranked: Vec<&C> = self.population.iter().collect();
+        ranked.sort_by(|a, b| rank(*a, *b));
+        let individuals: Vec<(f64, String)> = ranked.into_iter()
+            .take(log.k)
+            .map(|c| (c.fitness(), (log.describe)(c)))
+            .collect();
+
+        match &log.sink {
+            TopKSink::Events => self.subscribers.publish(Event::TopIndividuals {
+                generation: self.generation,
+                individuals,
+            }),
+            TopKSink::Writer(writer) => {
+                if let Ok(mut writer) = writer.lock() {
+                    for (i, (fitness, individual)) in individuals.iter().enumerate() {
+                        let _ = writeln!(writer, "{},{},{},\"{}\"", self.generation, i, fitness, individual);
+                    }
+                }
+            }
+        }
+    }
+
+    fn stats(&self) -> GenerationStats {
+        let size = self.population.len() as f64;
+        let total: f64 = self.population.iter().map(|p| p.fitness()).sum();
+        let mean = total / size;
+        let variance = self.population.iter().map(|p| (p.fitness() - mean).powi(2)).sum::<f64>() / size;
+        let worst = self.population.iter().map(|p| p.fitness()).fold(f64::INFINITY, f64::min);
+        let distinct: HashSet<u64> = self.population.iter().map(|p| p.fingerprint()).collect();
+
+        GenerationStats {
+            generation: self.generation,
+            best_fitness: self.fittest.fitness(),
+            best_cost: 1.0 / self.fittest.fitness(),
+            mean_fitness: mean,
+            std_dev: variance.sqrt(),
+            worst_fitness: worst,
+            diversity: distinct.len() as f64 / size,
+            population_hash: self.population_hash(),
+            gap: self.lower_bound
+                .filter(|&bound| bound > 0.0)
+                .map(|bound| (1.0 / self.fittest.fitness() - bound) / bound),
+            seed: self.generation_seeds.as_ref()
+                .filter(|_| self.generation > 0)
+                .map(|seeds| generation_seed(seeds.master, self.generation)),
+            mutation_rate: self.mutation_rate,
+        }
+    }
+
+    // Hash of the ranked population's fitness values and gene fingerprints.
+    // It is stable across platforms and toolchains, so hashes from separate
+    // runs can be compared to find the generation where they diverged.
+    fn population_hash(&self) -> u64 {
+        let mut ranked: Vec<&C> = self.population.iter().collect();
+        ranked.sort_by(|a, b| rank(*a, *b));
+
+        let mut hash = Fnv::new();
+        for p in ranked {
+            hash.feed(p.fitness().to_bits());
+            hash.feed(p.fingerprint());
+        }
+
+        hash.finish()
+    }
+
+    fn immigrate(&mut self) {
+        let immigration = match self.immigration.as_mut() {
+            Some(i) if self.generation.is_multiple_of(i.every) => i,
+            _ => return,
+        };
+
+        self.population.sort_by(rank);
+        let count = immigration.count.min(self.population.len());
+        let start = self.population.len() - count;
+        for slot in &mut self.population[start..] {
+            *slot = immigration.immigrants[immigration.next].clone();
+            immigration.next = (immigration.next + 1) % immigration.immigrants.len();
+        }
+    }
+
+    // Clones of the `k` fittest members, fittest first.
+    pub(crate) fn top(&self, k: usize) -> Vec<C> {
+        let mut ranked: Vec<&C> = self.population.iter().collect();
+        ranked.sort_by(|a, b| rank(*a, *b));
+        ranked.into_iter().take(k).cloned().collect()
+    }
+
+    // Replaces the weakest members with `incoming`, adopting the best of them
+    // as the fittest when it beats the current one.
+    pub(crate) fn replace_weakest(&mut self, incoming: &[C]) {
+        self.population.sort_by(rank);
+        let count = incoming.len().min(self.population.len());
+        let start = self.population.len() - count;
+        self.population[start..].clone_from_slice(&incoming[..count]);
+
+        for c in &incoming[..count] {
+            if rank(c, &self.fittest) == Ordering::Less {
+                self.fittest = c.clone();
+            }
+        }
+    }
+
+    fn find_fittest(&self) -> C {
+        let mut fittest = &self.population[0];
+
+        for p in &self.population[1..] {
+            if rank(p, fittest) == Ordering::Less {
+                fittest = p;
+            }
+        }
+
+        fittest.clone()
+    }
+
+    fn composition(&self) -> Composition {
+        Composition::new(self.population.len(), self.crossover_rate, self.survival_rate, self.elitism_count)
+    }
+
+    // Slots in each generation filled by crossover when nothing limits it.
+    fn offspring_slots(&self) -> usize {
+        self.composition().offspring
+    }
+
+    fn generate_next_generation(&mut self) {
+        if let Replacement::SteadyState { offspring } = self.replacement {
+            return self.replace_weakest_with_offspring(offspring);
+        }
+
+        let Composition { breeders: breeding_count, elites, retained, offspring: full } = self.composition();
+        let offspring = self.time_budget.as_ref().map_or(full, |b| b.offspring.min(full));
+        let copied = retained + full - offspring;
+
+        match self.selection {
+            // Only the members that can be picked need ranking: the breeding
+            // pool, the retained members and any filler copies. Those are
+            // moved to the front in linear time and sorted among themselves,
+            // which orders them exactly as a full sort would.
+            Selection::Truncation => {
+                let ranked = breeding_count.max(copied);
+                partition_fittest(&mut self.population, ranked);
+                self.population[..ranked].sort_by(rank);
+            }
+            // The copies still have to be the fittest members, and the
+            // elites the fittest of those.
+            Selection::Tournament { .. } | Selection::FitnessUniform => {
+                partition_fittest(&mut self.population, copied);
+                partition_fittest(&mut self.population[..copied], retained);
+                partition_fittest(&mut self.population[..retained], elites);
+            }
+        }
+
+        // The retained members, elites first, lead the population. Under
+        // truncation the prefix is ranked, so its first breeding_count
+        // members are the breeding pool.
+        let len = self.population.len();
+        let rate = self.mutation_rate;
+        let mut jobs = Vec::with_capacity(len);
+
+        for i in 0..elites {
+            jobs.push(Job::elite(i));
+        }
+        for i in elites..retained {
+            jobs.push(Job::new(i, None, rate, &mut self.rng));
+        }
+
+        let candidates = self.oversampling.map_or(offspring, |f| (offspring as f64 * f).ceil() as usize);
+        let pool = Pool::new(self.selection, &self.population, breeding_count);
+        for i in 0..candidates {
+            let mother = match self.selection {
+                Selection::Truncation => i % breeding_count,
+                _ => pool.draw(&self.population, &mut self.rng),
+            };
+            let father = self.draw_partner(mother, &pool);
+            jobs.push(Job::new(mother, Some(father), rate, &mut self.rng));
+        }
+
+        // Slots given up to stay within the time budget
+        for i in retained..copied {
+            jobs.push(Job::new(i, None, rate, &mut self.rng));
+        }
+
+        let mut bred = (self.breeder)(&self.population, &self.problem, &jobs, self.threads);
+        if candidates > offspring {
+            keep_fittest(&mut bred, &mut jobs, retained..retained + candidates, offspring);
+        }
+
+        debug_assert!(bred.len() == self.population.len());
+
+        let searched_from = self.local_search(&mut bred, retained..retained + offspring);
+        self.bred_best = bred_origin(&bred, &jobs, searched_from);
+        self.population = bred.into_iter().map(|(child, _)| child).collect();
+    }
+
+    // One steady-state step: breeds `offspring` children from the current
+    // population and overwrites its weakest members with them.
+    fn replace_weakest_with_offspring(&mut self, offspring: usize) {
+        let Composition { breeders: breeding_count, elites, .. } = self.composition();
+        let len = self.population.len();
+        let count = offspring.min(len - elites);
+
+        if self.selection == Selection::Truncation {
+            partition_fittest(&mut self.population, breeding_count);
+        }
+        let pool = Pool::new(self.selection, &self.population, breeding_count);
+        let mut jobs = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mother = pool.draw(&self.population, &mut self.rng);
+            let father = self.draw_partner(mother, &pool);
+            jobs.push(Job::new(mother, Some(father), self.mutation_rate, &mut self.rng));
+        }
+
+        let mut bred = (self.breeder)(&self.population, &self.problem, &jobs, self.threads);
+        let searched_from = self.local_search(&mut bred, 0..count);
+        self.bred_best = bred_origin(&bred, &jobs, searched_from);
+
+        partition_fittest(&mut self.population, len - count);
+        for (slot, (child, _)) in self.population[len - count..].iter_mut().zip(bred) {
+            *slot = child;
+        }
+    }
+
+    fn draw_partner(&mut self, mother: usize, pool: &Pool) -> usize {
+        let (mating, genome_distance) = match &self.mating {
+            Some(policy) => (policy.mating, policy.distance),
+            None => return pool.draw(&self.population, &mut self.rng),
+        };
+        let distance = |s: &Self, father: usize| genome_distance(&s.population[mother], &s.population[father]);
+
+        let mut father = pool.draw(&self.population, &mut self.rng);
+        match mating {
+            Mating::Random => {}
+            Mating::AvoidSelf => {
+                for _ in 0..MAX_REDRAWS {
+                    if father != mother {
+                        break;
+                    }
+                    father = pool.draw(&self.population, &mut self.rng);
+                }
+            }
+            Mating::AvoidIdentical => {
+                for _ in 0..MAX_REDRAWS {
+                    if father != mother && distance(self, father) > 0.0 {
+                        break;
+                    }
+                    father = pool.draw(&self.population, &mut self.rng);
+                }
+            }
+            Mating::Dissimilar { candidates } => {
+                let mut farthest = distance(self, father);
+                for _ in 1..candidates {
+                    let candidate = pool.draw(&self.population, &mut self.rng);
+                    let d = distance(self, candidate);
+                    if d > farthest {
+                        father = candidate;
+                        farthest = d;
+                    }
+                }
+            }
+        }
+        father
+    }
+
+    // Applies the local search to the fittest share of the offspring in
+    // `offspring`, returning each member's fitness before the search for
+    // those it improved.
+    fn local_search(&self, bred: &mut [(C, f64)], offspring: Range<usize>) -> Vec<Option<f64>> {
+        let mut searched_from = vec![None; bred.len()];
+        let search = match self.local_search.as_ref() {
+            Some(search) if !search.suspended && !offspring.is_empty() => search,
+            _ => return searched_from,
+        };
+
+        let mut ranked: Vec<usize> = offspring.collect();
+        ranked.sort_by(|&a, &b| rank(&bred[a].0, &bred[b].0));
+        let count = ((ranked.len() as f64 * search.rate).ceil() as usize).max(1);
+
+        for &i in ranked.iter().take(count) {
+            let before = bred[i].0.fitness();
+            if (search.improve)(&mut bred[i].0, &self.problem) {
+                searched_from[i] = Some(before);
+            }
+        }
+        searched_from
+    }
+}
+
+// Where the fittest of the `bred` members came from.
+fn bred_origin<C: Chromosome>(bred: &[(C, f64)], jobs: &[Job], searched_from: Vec<Option<f64>>) -> Option<Origin> {
+    bred.iter().zip(jobs).zip(searched_from)
+        .min_by(|a, b| rank(&((a.0).0).0, &((b.0).0).0))
+        .map(|(((child, unmutated_fitness), job), searched_from)| Origin {
+            fitness: child.fitness(),
+            unmutated_fitness: *unmutated_fitness,
+            crossover: job.father.is_some(),
+            mutated: job.mutate,
+            searched_from,
+        })
+}
+
+// Drops all but the `keep` fittest members of `bred[candidates]`, along
+// with the jobs that bred them.
+fn keep_fittest<C: Chromosome>(bred: &mut Vec<(C, f64)>, jobs: &mut Vec<Job>, candidates: Range<usize>, keep: usize) {
+    let mut order: Vec<usize> = candidates.collect();
+    if keep > 0 {
+        order.select_nth_unstable_by(keep - 1, |&a, &b| rank(&bred[a].0, &bred[b].0));
+    }
+
+    let mut kept = vec![true; bred.len()];
+    for &i in &order[keep..] {
+        kept[i] = false;
+    }
+    let mut flags = kept.iter();
+    bred.retain(|_| *flags.next().unwrap_or(&true));
+    let mut flags = kept.iter();
+    jobs.retain(|_| *flags.next().unwrap_or(&true));
+}
+
+impl<C, R> Simulation<C, R>
+where
+    C: Chromosome,
+    C::Problem: Clone,
+    R: Rng + Clone,
+{
+    // Runs a short pilot from the current state for each candidate rate,
+    // spending about `evaluations` fitness evaluations on each, and keeps the
+    // rate whose best cost fell fastest. Pilots run on clones sharing the same
+    // RNG state, so the candidates are compared on identical random streams.
+    pub fn calibrate_mutation_rate(&mut self, candidates: &[f64], evaluations: usize) -> Result<f64, Error> {
+        for &rate in candidates {
+            check_rate("mutation_rate", rate)?;
+        }
+
+        let generations = (evaluations / self.population.len()).max(1);
+        let start_cost = 1.0 / self.fittest.fitness();
+        let mut best_rate = self.mutation_rate;
+        let mut best_slope = f64::NEG_INFINITY;
+
+        for &rate in candidates {
+            let mut pilot = self.clone();
+            pilot.mutation_rate = rate;
+            pilot.subscribers.clear();
+
+            let end_cost = pilot.iter().take(generations).last()
+                .map_or(start_cost, |stats| stats.best_cost);
+            let slope = (start_cost - end_cost) / (generations * self.population.len()) as f64;
+
+            if slope > best_slope {
+                best_slope = slope;
+                best_rate = rate;
+            }
+        }
+
+        self.mutation_rate = best_rate;
+        Ok(best_rate)
+    }
+}
+
+pub struct Generations<'a, C: Chromosome, R> {
+    simulation: &'a mut Simulation<C, R>,
+}
+
+impl<'a, C: Chromosome, R: Rng> Iterator for Generations<'a, C, R> {
+    type Item = GenerationStats;
+
+    fn next(&mut self) -> Option<GenerationStats> {
+        Some(self.simulation.step())
+    }
+}
+
+// The seed of generation `generation` under master seed `master`: the two
+// are summed and mixed with SplitMix64, so neighbouring generations get
+// unrelated seeds.
+pub fn generation_seed(master: u64, generation: usize) -> u64 {
+    let mut z = master.wrapping_add(generation as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::initial_population;
+
+    fn cities() -> Vec<City> {
+        (0..8).map(|i| City::new((i % 4) as f64, (i / 4) as f64)).collect()
+    }
+
+    fn simulation(size: usize, crossover_rate: f64, survival_rate: f64) -> Result<Simulation, Error> {
+        let cities = cities();
+        let mut rng = SmallRng::seed_from_u64(7);
+        let population = initial_population(&cities, size, &mut rng);
+        Simulation::with_rng(population, cities, 20, crossover_rate, 0.2, survival_rate, rng)
+    }
+
+    #[test]
+    fn composition_of_the_smallest_population() {
+        let composition = Composition::new(2, 0.8, 0.2, 0);
+        assert_eq!(composition, Composition { breeders: 1, elites: 0, retained: 0, offspring: 2 });
+    }
+
+    #[test]
+    fn at_least_one_member_breeds_and_one_slot_is_bred() {
+        assert_eq!(Composition::new(5, 0.0, 0.0, 0).breeders, 1);
+        let composition = Composition::new(5, 1.0, 1.0, 0);
+        assert_eq!((composition.retained, composition.offspring), (4, 1));
+    }
+
+    #[test]
+    fn elites_are_retained_even_when_survivors_are_fewer() {
+        let composition = Composition::new(10, 0.5, 0.2, 3);
+        assert_eq!((composition.breeders, composition.elites, composition.retained), (5, 3, 3));
+        assert_eq!(composition.offspring, 7);
+    }
+
+    #[test]
+    fn shares_are_not_lost_to_rounding() {
+        assert_eq!(Composition::new(100, 0.29, 0.0, 0).breeders, 29);
+    }
+
+    #[test]
+    fn populations_below_two_are_rejected() {
+        assert!(matches!(simulation(0, 0.8, 0.2), Err(Error::EmptyPopulation)));
+        assert!(matches!(simulation(1, 0.8, 0.2), Err(Error::PopulationTooSmall { size: 1 })));
+    }
+
+    #[test]
+    fn tiny_populations_run_under_every_scheme() {
+        let selections = [Selection::Truncation, Selection::Tournament { size: 2 }, Selection::FitnessUniform];
+        for size in 2..6 {
+            for &(crossover_rate, survival_rate) in &[(0.0, 0.0), (0.5, 0.5), (1.0, 1.0)] {
+                for &selection in &selections {
+                    let mut sim = simulation(size, crossover_rate, survival_rate).unwrap()
+                        .with_elitism(1).unwrap()
+                        .with_selection(selection).unwrap();
+                    let start = sim.fittest().fitness();
+                    let stats = sim.iter().take(10).last().unwrap();
+                    assert_eq!(sim.population.len(), size);
+                    assert!(stats.best_fitness >= start);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn elites_survive_every_generation() {
+        let mut sim = simulation(4, 1.0, 0.0).unwrap().with_elitism(3).unwrap();
+        for _ in 0..10 {
+            let mut before: Vec<f64> = sim.population.iter().map(|p| p.fitness()).collect();
+            before.sort_by(|a, b| b.partial_cmp(a).unwrap());
+            sim.step();
+            let mut after: Vec<f64> = sim.population.iter().map(|p| p.fitness()).collect();
+            after.sort_by(|a, b| b.partial_cmp(a).unwrap());
+            assert!(after[..3].iter().zip(&before[..3]).all(|(a, b)| a >= b));
+        }
+    }
+
+    #[test]
+    fn copies_given_up_to_the_budget_are_the_fittest_under_tournaments() {
+        let mut sim = simulation(6, 0.5, 0.0).unwrap()
+            .with_selection(Selection::Tournament { size: 2 }).unwrap();
+        sim.mutation_rate = 0.0;
+        sim.time_budget = Some(TimeBudget { budget: Duration::from_secs(3600), offspring: 1 });
+
+        let mut before: Vec<f64> = sim.population.iter().map(|p| p.fitness()).collect();
+        before.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        sim.generate_next_generation();
+
+        // Every member but the one offspring is an unchanged copy.
+        let after: Vec<f64> = sim.population.iter().map(|p| p.fitness()).collect();
+        for fitness in &before[..5] {
+            assert!(after.contains(fitness));
+        }
+    }
+
+    #[test]
+    fn steady_state_never_overwrites_elites() {
+        let mut sim = simulation(3, 0.8, 0.2).unwrap()
+            .with_elitism(2).unwrap()
+            .with_replacement(Replacement::SteadyState { offspring: 2 }).unwrap();
+        let best = sim.fittest().fitness();
+        for _ in 0..10 {
+            sim.step();
+            assert_eq!(sim.population.len(), 3);
+            assert!(sim.population.iter().any(|p| p.fitness() >= best));
+        }
+    }
+
+    #[test]
+    fn estimates_follow_the_composition_and_leave_the_run_alone() {
+        let sim = simulation(10, 0.5, 0.2).unwrap();
+        let before: Vec<Vec<usize>> = sim.population.iter().map(|p| p.order.clone()).collect();
+        let estimate = sim.estimate(2);
+        assert_eq!(estimate.evaluations_per_generation, 9);
+        assert_eq!(estimate.run_time, estimate.time_per_generation * 20);
+        assert!(estimate.memory_bytes >= 64 * mem::size_of::<f64>() + 19 * 8 * mem::size_of::<usize>());
+        assert_eq!(sim.generation, 0);
+        assert_eq!(sim.population.iter().map(|p| p.order.clone()).collect::<Vec<_>>(), before);
+
+        let oversampled = sim.clone().with_oversampling(2.0).unwrap().estimate(1);
+        assert_eq!(oversampled.evaluations_per_generation, 18);
+        let steady = sim.with_replacement(Replacement::SteadyState { offspring: 3 }).unwrap().estimate(1);
+        assert_eq!(steady.evaluations_per_generation, 3);
+    }
+
+    #[test]
+    fn a_resumed_run_continues_where_it_left_off() {
+        let resume = |sim: &mut Simulation| {
+            let mut text = Vec::new();
+            sim.write_checkpoint(&mut text).unwrap();
+            Simulation::<Path, SmallRng>::resume_from_str(&String::from_utf8(text).unwrap()).unwrap()
+        };
+        let orders = |sim: &Simulation| sim.population.iter().map(|p| p.order.clone()).collect::<Vec<_>>();
+
+        let mut uninterrupted = simulation(20, 0.8, 0.2).unwrap().with_generation_seeds(5);
+        uninterrupted.step_n(12);
+        let mut saved = simulation(20, 0.8, 0.2).unwrap().with_generation_seeds(5);
+        saved.step_n(4);
+        let mut resumed = resume(&mut saved);
+        resumed.step_n(8);
+        assert_eq!(orders(&resumed), orders(&uninterrupted));
+        assert_eq!(resumed.fittest().order, uninterrupted.fittest().order);
+        assert_eq!(resumed.generation, uninterrupted.generation);
+
+        // Without generation seeds saving reseeds the run, and the resumed
+        // one follows the saved one exactly.
+        let mut saved = simulation(20, 0.8, 0.2).unwrap();
+        saved.step_n(4);
+        let mut resumed = resume(&mut saved);
+        saved.step_n(8);
+        resumed.step_n(8);
+        assert_eq!(orders(&resumed), orders(&saved));
+    }
+
+}
@@ -0,0 +1,85 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path as FsPath;
+
+use crate::error::Error;
+use crate::simulation::GenerationStats;
+
+// Per-generation history kept by Simulation::with_stats_recorder for
+// convergence analysis after a run.
+#[derive(Clone, Debug, Default)]
+pub struct StatsRecorder {
+    history: Vec<GenerationStats>,
+}
+
+impl StatsRecorder {
+    pub fn record(&mut self, stats: &GenerationStats) {
+        self.history.push(stats.clone());
+    }
+
+    pub fn history(&self) -> &[GenerationStats] {
+        &self.history
+    }
+
+    pub fn into_history(self) -> Vec<GenerationStats> {
+        self.history
+    }
+
+    pub fn write_csv<W: Write>(&self, writer: W) -> Result<(), Error> {
+        write_history_csv(&self.history, writer)
+    }
+
+    pub fn save_csv(&self, file: &FsPath) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(file)?);
+        self.write_csv(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+// The numeric series exporters log per generation, by name. The gap is
+// left out when no lower bound was set.
+pub(crate) fn metrics(stats: &GenerationStats) -> Vec<(&'static str, f64)> {
+    let mut metrics = vec![
+        ("best_fitness", stats.best_fitness),
+        ("best_cost", stats.best_cost),
+        ("mean_fitness", stats.mean_fitness),
+        ("std_dev", stats.std_dev),
+        ("worst_fitness", stats.worst_fitness),
+        ("diversity", stats.diversity),
+        ("mutation_rate", stats.mutation_rate),
+    ];
+    if let Some(gap) = stats.gap {
+        metrics.push(("gap", gap));
+    }
+    metrics
+}
+
+// One header line, then one line per generation. The gap and seed columns
+// are empty when no lower bound or generation seeds were set.
+pub(crate) fn write_history_csv<W: Write>(history: &[GenerationStats], mut writer: W) -> Result<(), Error> {
+    writeln!(
+        writer,
+        "generation,best_fitness,best_cost,mean_fitness,std_dev,worst_fitness,diversity,gap,population_hash,seed,mutation_rate"
+    )?;
+    for s in history {
+        let gap = s.gap.map_or(String::new(), |gap| gap.to_string());
+        let seed = s.seed.map_or(String::new(), |seed| seed.to_string());
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{},{:016x},{},{}",
+            s.generation,
+            s.best_fitness,
+            s.best_cost,
+            s.mean_fitness,
+            s.std_dev,
+            s.worst_fitness,
+            s.diversity,
+            gap,
+            s.population_hash,
+            seed,
+            s.mutation_rate
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::Error;
+use crate::events::{Event, Subscriber};
+use crate::simulation::GenerationStats;
+use crate::stats::metrics;
+
+// Writes per-generation metrics as a TensorBoard event file, one scalar
+// series per GenerationStats field, with the generation as the step. Point
+// `tensorboard --logdir` at the directory to watch a run.
+pub struct TensorBoardWriter {
+    file: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl TensorBoardWriter {
+    // Creates `dir` if needed and starts a new event file in it.
+    pub fn create(dir: &FsPath) -> Result<TensorBoardWriter, Error> {
+        fs::create_dir_all(dir)?;
+        let file = dir.join(format!("events.out.tfevents.{}.genetic", wall_time() as u64));
+        let mut writer = TensorBoardWriter { writer: BufWriter::new(File::create(&file)?), file };
+
+        let mut event = Vec::new();
+        put_double(&mut event, 1, wall_time());
+        put_bytes(&mut event, 3, b"brain.Event:2");
+        writer.record(&event)?;
+        writer.writer.flush()?;
+        Ok(writer)
+    }
+
+    pub fn file(&self) -> &FsPath {
+        &self.file
+    }
+
+    // Appends one event holding every metric of `stats` and flushes it, so
+    // TensorBoard sees the generation straight away.
+    pub fn log(&mut self, stats: &GenerationStats) -> Result<(), Error> {
+        let mut summary = Vec::new();
+        for (tag, value) in metrics(stats) {
+            let mut entry = Vec::new();
+            put_bytes(&mut entry, 1, tag.as_bytes());
+            put_key(&mut entry, 2, 5);
+            entry.extend_from_slice(&(value as f32).to_le_bytes());
+            put_bytes(&mut summary, 1, &entry);
+        }
+
+        let mut event = Vec::new();
+        put_double(&mut event, 1, wall_time());
+        put_key(&mut event, 2, 0);
+        put_varint(&mut event, stats.generation as u64);
+        put_bytes(&mut event, 5, &summary);
+
+        self.record(&event)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    // A subscriber that logs every Event::GenerationCompleted. Writes are
+    // best-effort; a failing write does not stop the run.
+    pub fn into_subscriber(self) -> Subscriber {
+        let mut writer = self;
+        Arc::new(Mutex::new(move |event: &Event| {
+            if let Event::GenerationCompleted(stats) = event {
+                let _ = writer.log(stats);
+            }
+        }))
+    }
+
+    // One TFRecord: the length and its masked CRC, then the data and its
+    // masked CRC.
+    fn record(&mut self, data: &[u8]) -> Result<(), Error> {
+        let length = (data.len() as u64).to_le_bytes();
+        self.writer.write_all(&length)?;
+        self.writer.write_all(&masked_crc(&length).to_le_bytes())?;
+        self.writer.write_all(data)?;
+        self.writer.write_all(&masked_crc(data).to_le_bytes())?;
+        Ok(())
+    }
+}
+
+fn wall_time() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64())
+}
+
+// Protocol buffer encoding of the few Event and Summary fields written.
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn put_key(buf: &mut Vec<u8>, field: u64, wire_type: u64) {
+    put_varint(buf, field << 3 | wire_type);
+}
+
+fn put_double(buf: &mut Vec<u8>, field: u64, value: f64) {
+    put_key(buf, field, 1);
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_bytes(buf: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    put_key(buf, field, 2);
+    put_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+// CRC-32C (Castagnoli), masked as TFRecord requires.
+fn masked_crc(data: &[u8]) -> u32 {
+    let crc = crc32c(data);
+    crc.rotate_right(15).wrapping_add(0xa282_ead8)
+}
+
+fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0x82f6_3b78 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
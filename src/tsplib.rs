@@ -0,0 +1,246 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path as FsPath;
+
+use crate::city::City;
+use crate::error::Error;
+use crate::metric::DistanceMetric;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EdgeWeightType {
+    Euc2d,
+    // Coordinates are DDD.MM latitude/longitude; cities are loaded with
+    // x = latitude and y = longitude in decimal degrees.
+    Geo,
+}
+
+#[derive(Clone)]
+pub struct TsplibInstance {
+    pub name: String,
+    pub edge_weight_type: EdgeWeightType,
+    pub cities: Vec<City>,
+}
+
+impl TsplibInstance {
+    // Length of the closed tour under TSPLIB's own distance function (rounded
+    // Euclidean or rounded geographical), so results can be compared with
+    // published optima.
+    pub fn tour_length(&self, order: &[usize]) -> f64 {
+        let n = order.len();
+        (0..n).map(|i| self.distance(order[i], order[(i + 1) % n])).sum()
+    }
+
+    fn distance(&self, i: usize, j: usize) -> f64 {
+        self.edge_weight_type.distance(&self.cities[i], &self.cities[j])
+    }
+}
+
+// TSPLIB's own distance functions, so a solver can optimise exactly the
+// lengths tour_length reports: pass the instance's edge_weight_type to
+// TspProblem::with_metric or Simulation::with_metric.
+impl DistanceMetric for EdgeWeightType {
+    fn distance(&self, a: &City, b: &City) -> f64 {
+        match self {
+            EdgeWeightType::Euc2d => ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt().round(),
+            EdgeWeightType::Geo => {
+                const RRR: f64 = 6378.388;
+                let (lat_i, lon_i) = (geo_radians(a.x), geo_radians(a.y));
+                let (lat_j, lon_j) = (geo_radians(b.x), geo_radians(b.y));
+                let q1 = (lon_i - lon_j).cos();
+                let q2 = (lat_i - lat_j).cos();
+                let q3 = (lat_i + lat_j).cos();
+                (RRR * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0).trunc()
+            }
+        }
+    }
+}
+
+// TSPLIB converts decimal degrees with its own truncated value of pi.
+#[allow(clippy::approx_constant)]
+fn geo_radians(degrees: f64) -> f64 {
+    3.141592 * degrees / 180.0
+}
+
+// DDD.MM (degrees and minutes) to decimal degrees.
+fn ddd_mm(value: f64) -> f64 {
+    let degrees = value.trunc();
+    degrees + 5.0 * (value - degrees) / 3.0
+}
+
+pub fn load_tsp(file: &FsPath) -> Result<TsplibInstance, Error> {
+    parse_tsp(BufReader::new(File::open(file)?))
+}
+
+pub fn parse_tsp<R: BufRead>(reader: R) -> Result<TsplibInstance, Error> {
+    let mut name = String::new();
+    let mut edge_weight_type = None;
+    let mut dimension = None;
+    let mut in_coords = false;
+    let mut coords: Vec<(usize, f64, f64)> = Vec::new();
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        let parse_error = |reason: &str| Error::Parse { line: number + 1, reason: reason.to_string() };
+
+        if line.is_empty() {
+            continue;
+        }
+        if line == "EOF" {
+            break;
+        }
+
+        if in_coords {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() == 3 {
+                let id = fields[0].parse().map_err(|_| parse_error("bad node id"))?;
+                let x = fields[1].parse().map_err(|_| parse_error("bad x coordinate"))?;
+                let y = fields[2].parse().map_err(|_| parse_error("bad y coordinate"))?;
+                coords.push((id, x, y));
+                continue;
+            }
+            in_coords = false;
+        }
+
+        if line.starts_with("NODE_COORD_SECTION") {
+            in_coords = true;
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim();
+            match key.trim() {
+                "NAME" => name = value.to_string(),
+                "DIMENSION" => {
+                    dimension = Some(value.parse::<usize>().map_err(|_| parse_error("bad DIMENSION"))?);
+                }
+                "EDGE_WEIGHT_TYPE" => {
+                    edge_weight_type = Some(match value {
+                        "EUC_2D" => EdgeWeightType::Euc2d,
+                        "GEO" => EdgeWeightType::Geo,
+                        _ => return Err(parse_error(&format!("unsupported EDGE_WEIGHT_TYPE {}", value))),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let edge_weight_type = edge_weight_type
+        .ok_or_else(|| Error::Parse { line: 0, reason: "missing EDGE_WEIGHT_TYPE".to_string() })?;
+    if let Some(dimension) = dimension {
+        if dimension != coords.len() {
+            return Err(Error::LengthMismatch { expected: dimension, found: coords.len() });
+        }
+    }
+
+    // Node ids are 1-based; order cities by id.
+    coords.sort_by_key(|&(id, _, _)| id);
+    let cities = coords.into_iter().map(|(_, x, y)| match edge_weight_type {
+        EdgeWeightType::Euc2d => City::new(x, y),
+        EdgeWeightType::Geo => City::new(ddd_mm(x), ddd_mm(y)),
+    }).collect();
+
+    Ok(TsplibInstance { name, edge_weight_type, cities })
+}
+
+pub fn load_tour(file: &FsPath) -> Result<Vec<usize>, Error> {
+    parse_tour(BufReader::new(File::open(file)?))
+}
+
+// Reads the TOUR_SECTION of an `.opt.tour` file as 0-based city indices.
+pub fn parse_tour<R: BufRead>(reader: R) -> Result<Vec<usize>, Error> {
+    let mut in_tour = false;
+    let mut tour = Vec::new();
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+
+        if !in_tour {
+            in_tour = line.starts_with("TOUR_SECTION");
+            continue;
+        }
+
+        for token in line.split_whitespace() {
+            if token == "-1" || token == "EOF" {
+                return Ok(tour);
+            }
+            let id: usize = token.parse()
+                .map_err(|_| Error::Parse { line: number + 1, reason: format!("bad node id {}", token) })?;
+            if id == 0 {
+                return Err(Error::Parse { line: number + 1, reason: "node ids start at 1".to_string() });
+            }
+            tour.push(id - 1);
+        }
+    }
+
+    Ok(tour)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EUC: &str = "NAME: square\nTYPE: TSP\nDIMENSION: 4\nEDGE_WEIGHT_TYPE: EUC_2D\nNODE_COORD_SECTION\n1 0 0\n3 3 4\n2 3 0\n4 0 4\nEOF\n";
+
+    fn parse(text: &str) -> Result<TsplibInstance, Error> {
+        parse_tsp(text.as_bytes())
+    }
+
+    #[test]
+    fn euc_2d_cities_are_ordered_by_id() {
+        let instance = parse(EUC).unwrap();
+        assert_eq!(instance.name, "square");
+        assert_eq!(instance.edge_weight_type, EdgeWeightType::Euc2d);
+        let coords: Vec<(f64, f64)> = instance.cities.iter().map(|c| (c.x, c.y)).collect();
+        assert_eq!(coords, vec![(0.0, 0.0), (3.0, 0.0), (3.0, 4.0), (0.0, 4.0)]);
+        assert_eq!(instance.tour_length(&[0, 1, 2, 3]), 14.0);
+        assert_eq!(instance.tour_length(&[0, 2, 1, 3]), 18.0);
+    }
+
+    #[test]
+    fn geo_coordinates_are_read_as_degrees_and_minutes() {
+        let text = "NAME: burma2\nEDGE_WEIGHT_TYPE: GEO\nNODE_COORD_SECTION\n1 16.47 96.10\n2 16.47 94.44\nEOF\n";
+        let instance = parse(text).unwrap();
+        assert_eq!(instance.edge_weight_type, EdgeWeightType::Geo);
+        assert!((instance.cities[0].x - (16.0 + 47.0 / 60.0)).abs() < 1e-9);
+        assert!((instance.cities[1].y - (94.0 + 44.0 / 60.0)).abs() < 1e-9);
+        // The first edge of burma14.
+        assert_eq!(instance.tour_length(&[0, 1]), 2.0 * 153.0);
+    }
+
+    #[test]
+    fn unsupported_edge_weight_types_are_rejected() {
+        let text = "NAME: x\nEDGE_WEIGHT_TYPE: EXPLICIT\nNODE_COORD_SECTION\n1 0 0\nEOF\n";
+        assert!(matches!(parse(text), Err(Error::Parse { line: 2, .. })));
+        assert!(matches!(parse("NAME: x\nNODE_COORD_SECTION\n1 0 0\n"), Err(Error::Parse { .. })));
+    }
+
+    #[test]
+    fn malformed_lines_are_rejected() {
+        let bad_x = EUC.replace("3 3 4", "3 three 4");
+        assert!(matches!(parse(&bad_x), Err(Error::Parse { line: 7, .. })));
+        let bad_id = EUC.replace("2 3 0", "two 3 0");
+        assert!(matches!(parse(&bad_id), Err(Error::Parse { line: 8, .. })));
+        let bad_dimension = EUC.replace("DIMENSION: 4", "DIMENSION: four");
+        assert!(matches!(parse(&bad_dimension), Err(Error::Parse { line: 3, .. })));
+        let short = EUC.replace("DIMENSION: 4", "DIMENSION: 5");
+        assert!(matches!(parse(&short), Err(Error::LengthMismatch { expected: 5, found: 4 })));
+    }
+
+    #[test]
+    fn tours_end_at_minus_one_or_eof() {
+        let tour = "NAME: square.opt.tour\nTYPE: TOUR\nTOUR_SECTION\n1\n3\n2 4\n-1\n5\n";
+        assert_eq!(parse_tour(tour.as_bytes()).unwrap(), vec![0, 2, 1, 3]);
+        let tour = "TOUR_SECTION\n1 2\n4 3 EOF\n5\n";
+        assert_eq!(parse_tour(tour.as_bytes()).unwrap(), vec![0, 1, 3, 2]);
+        assert_eq!(parse_tour("TOUR_SECTION\n2\n1\n".as_bytes()).unwrap(), vec![1, 0]);
+    }
+
+    #[test]
+    fn malformed_tours_are_rejected() {
+        assert!(matches!(parse_tour("TOUR_SECTION\n1\nx\n-1\n".as_bytes()), Err(Error::Parse { line: 3, .. })));
+        assert!(matches!(parse_tour("TOUR_SECTION\n0\n-1\n".as_bytes()), Err(Error::Parse { line: 2, .. })));
+    }
+}